@@ -0,0 +1,102 @@
+//! Compares the per-recipient `Mailbox` with the flat `Vec` it replaced.
+//!
+//! Run with `cargo bench --bench mailbox`. Both mailboxes are filled with
+//! 100,000 messages spread over 1,000 CubeSats and then drained one delivery at
+//! a time, satellite by satellite.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use rusty_dusty::concepts::own::{CubeSat, Mailbox, Message};
+
+const MESSAGES: u64 = 100_000;
+const SATS: u64 = 1_000;
+
+/// The original mailbox: one `Vec`, scanned from the back on every delivery.
+#[derive(Default)]
+struct VecMailbox {
+    messages: Vec<Message>,
+}
+
+impl VecMailbox {
+    fn post(&mut self, msg: Message) {
+        self.messages.push(msg);
+    }
+
+    fn deliver(&mut self, recipient: &CubeSat) -> Option<Message> {
+        for i in (0..self.messages.len()).rev() {
+            if self.messages[i].to == recipient.id {
+                return Some(self.messages.remove(i));
+            }
+        }
+        None
+    }
+}
+
+/// The operations being compared.
+trait Queue: Default {
+    fn post(&mut self, msg: Message);
+    fn deliver(&mut self, recipient: &CubeSat) -> Option<Message>;
+}
+
+impl Queue for VecMailbox {
+    fn post(&mut self, msg: Message) {
+        VecMailbox::post(self, msg)
+    }
+
+    fn deliver(&mut self, recipient: &CubeSat) -> Option<Message> {
+        VecMailbox::deliver(self, recipient)
+    }
+}
+
+impl Queue for Mailbox {
+    fn post(&mut self, msg: Message) {
+        Mailbox::post(self, msg)
+    }
+
+    fn deliver(&mut self, recipient: &CubeSat) -> Option<Message> {
+        Mailbox::deliver(self, recipient)
+    }
+}
+
+fn messages() -> impl Iterator<Item = Message> {
+    (0..MESSAGES).map(|i| Message {
+        to: i % SATS,
+        content: format!("msg {}", i),
+    })
+}
+
+/// Times filling a mailbox and then draining it completely.
+fn bench<Q: Queue>(name: &str) {
+    let mut mailbox = Q::default();
+
+    let start = Instant::now();
+    messages().for_each(|msg| mailbox.post(msg));
+    let posted = start.elapsed();
+
+    let start = Instant::now();
+    let mut delivered = 0;
+    for id in 0..SATS {
+        let sat = CubeSat { id };
+        while let Some(msg) = mailbox.deliver(&sat) {
+            black_box(msg);
+            delivered += 1;
+        }
+    }
+    let drained = start.elapsed();
+    assert_eq!(delivered, MESSAGES);
+
+    println!(
+        "{:<8} post {:>10.2?}  deliver {:>10.2?}  ({:.0} ns per delivery)",
+        name,
+        posted,
+        drained,
+        drained.as_nanos() as f64 / MESSAGES as f64
+    );
+}
+
+fn main() {
+    println!("{} messages for {} CubeSats", MESSAGES, SATS);
+    bench::<VecMailbox>("Vec");
+    bench::<Mailbox>("indexed");
+}
@@ -0,0 +1,54 @@
+//! # Exercise: errors_parse
+//!
+//! Report failures with `Result` and a custom error type, and pass errors up
+//! with the `?` operator. Replace each `todo!()` and check your work with:
+//!
+//! ```text
+//! cargo run -- exercise errors_parse
+//! ```
+
+use std::fmt;
+use std::num::ParseIntError;
+
+/// Why an age could not be read.
+#[derive(Debug, PartialEq)]
+pub enum AgeError {
+    /// The input was empty or only whitespace.
+    Empty,
+    /// The input was not a whole number.
+    NotANumber(ParseIntError),
+    /// The number was above 150.
+    TooOld(u32),
+}
+
+impl fmt::Display for AgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AgeError::Empty => write!(f, "no age given"),
+            AgeError::NotANumber(err) => write!(f, "not a number: {}", err),
+            AgeError::TooOld(age) => write!(f, "{} is too old", age),
+        }
+    }
+}
+
+/// Parses an age such as `" 42 "`, ignoring surrounding whitespace.
+pub fn parse_age(input: &str) -> Result<u32, AgeError> {
+    let _ = input;
+    todo!()
+}
+
+/// Adds up a list of numbers, stopping at the first one that does not parse.
+///
+/// Hint: `?` returns the `ParseIntError` for you.
+pub fn sum_all(inputs: &[&str]) -> Result<i64, ParseIntError> {
+    let _ = inputs;
+    todo!()
+}
+
+/// Parses every age, collecting them all or returning the first error.
+///
+/// Hint: an iterator of `Result`s can be collected into a `Result<Vec<_>, _>`.
+pub fn parse_ages(inputs: &[&str]) -> Result<Vec<u32>, AgeError> {
+    let _ = inputs;
+    todo!()
+}
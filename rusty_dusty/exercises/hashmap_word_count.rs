@@ -0,0 +1,32 @@
+//! # Exercise: hashmap_word_count
+//!
+//! Count and look up values with `HashMap` and its entry API. Replace each
+//! `todo!()` and check your work with:
+//!
+//! ```text
+//! cargo run -- exercise hashmap_word_count
+//! ```
+
+use std::collections::HashMap;
+
+/// Counts how often each word appears, ignoring case.
+///
+/// Words are separated by whitespace. Hint: `entry(..).or_insert(0)`.
+pub fn word_count(text: &str) -> HashMap<String, usize> {
+    let _ = text;
+    todo!()
+}
+
+/// Returns the most frequent word and its count.
+///
+/// Ties go to the word that comes first alphabetically; an empty text has none.
+pub fn most_common(text: &str) -> Option<(String, usize)> {
+    let _ = text;
+    todo!()
+}
+
+/// Groups words by their first letter, keeping them in the order they appear.
+pub fn group_by_initial<'a>(words: &[&'a str]) -> HashMap<char, Vec<&'a str>> {
+    let _ = words;
+    todo!()
+}
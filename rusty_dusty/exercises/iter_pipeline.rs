@@ -0,0 +1,36 @@
+//! # Exercise: iter_pipeline
+//!
+//! Build results with iterator adapters and closures instead of index loops.
+//! Replace each `todo!()` and check your work with:
+//!
+//! ```text
+//! cargo run -- exercise iter_pipeline
+//! ```
+
+/// Squares every odd number, keeping their order.
+///
+/// Hint: `filter` then `map`, then `collect`.
+pub fn squares_of_odds(numbers: &[i32]) -> Vec<i32> {
+    let _ = numbers;
+    todo!()
+}
+
+/// Returns a closure that adds `n` to its argument.
+pub fn make_adder(n: i32) -> impl Fn(i32) -> i32 {
+    let _ = n;
+    |_| todo!()
+}
+
+/// Applies `f` to `value` `times` times.
+pub fn apply_n<F: Fn(i32) -> i32>(f: F, times: usize, value: i32) -> i32 {
+    let _ = (f, times, value);
+    todo!()
+}
+
+/// The total number of characters in all words longer than `min_len` characters.
+///
+/// Hint: `sum` works on an iterator of `usize`.
+pub fn total_long_word_length(words: &[&str], min_len: usize) -> usize {
+    let _ = (words, min_len);
+    todo!()
+}
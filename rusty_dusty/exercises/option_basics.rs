@@ -0,0 +1,36 @@
+//! # Exercise: option_basics
+//!
+//! Handle values that may be missing with `Option` instead of sentinel values.
+//! Replace each `todo!()` and check your work with:
+//!
+//! ```text
+//! cargo run -- exercise option_basics
+//! ```
+
+/// Returns the first even number in `numbers`, or `None` if there is none.
+pub fn first_even(numbers: &[i32]) -> Option<i32> {
+    let _ = numbers;
+    todo!()
+}
+
+/// Divides `a` by `b`, returning `None` instead of panicking when `b` is zero.
+pub fn checked_divide(a: i32, b: i32) -> Option<i32> {
+    let _ = (a, b);
+    todo!()
+}
+
+/// Returns the length of the name, or 0 when there is no name.
+///
+/// Hint: `map` and `unwrap_or` avoid writing out the `match`.
+pub fn name_length(name: Option<&str>) -> usize {
+    let _ = name;
+    todo!()
+}
+
+/// Returns the initial of the name in upper case, if there is a non-empty name.
+///
+/// Hint: `and_then` chains two steps that can each come up empty.
+pub fn initial(name: Option<&str>) -> Option<char> {
+    let _ = name;
+    todo!()
+}
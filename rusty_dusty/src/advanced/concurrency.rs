@@ -1,31 +1,60 @@
+use std::io::{self, Write};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
 use std::time::Duration;
 use std::sync::mpsc;
 use threadpool::ThreadPool;
 
-fn basic_threads_example() {
-    println!("\n=== Basic Threads ===");
+use crate::demo::{Category, Entry, Mask};
+use crate::params::{Param, ParamKind};
+
+/// Collects the lines printed by several threads, in the order they happen,
+/// so they can be written to the demo's output afterwards.
+#[derive(Clone, Default)]
+struct ThreadLog(Arc<Mutex<Vec<String>>>);
+
+impl ThreadLog {
+    fn line(&self, line: String) {
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        for line in self.0.lock().unwrap().drain(..) {
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Spawns a thread and counts on it alongside the main thread.
+pub fn basic_threads_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n=== Basic Threads ===")?;
     
+    let log = ThreadLog::default();
+
     // spawn a new thread using spawn.
-    let handle = thread::spawn(|| {
+    let spawned_log = log.clone();
+    let handle = thread::spawn(move || {
         for i in 1..=3 {
-            println!("Spawned thread counting: {}", i);
+            spawned_log.line(format!("Spawned thread counting: {}", i));
             thread::sleep(Duration::from_millis(100));
         }
     });
 
     for i in 1..=3 {
-        println!("Main thread counting: {}", i);
+        log.line(format!("Main thread counting: {}", i));
         thread::sleep(Duration::from_millis(100));
     }
 
     // wait for spawned thread to finish.
     handle.join().unwrap();
+
+    log.write_to(out)
 }
 
-fn channel_example() {
-    println!("\n=== Channels ===");
+/// Sends messages from two producer threads over an `mpsc` channel.
+pub fn channel_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n=== Channels ===")?;
     
     // create a channel.
     let (sender, receiver) = mpsc::channel();
@@ -42,23 +71,28 @@ fn channel_example() {
     });
 
     for _ in 0..2 {
-        println!("Received: {}", receiver.recv().unwrap());
+        writeln!(out, "Received: {}", receiver.recv().unwrap())?;
     }
+
+    Ok(())
 }
 
-fn shared_state_example() {
-    println!("\n=== Shared State (Arc and Mutex) ===");
+/// Increments a counter shared between threads with `Arc<Mutex<_>>`.
+pub fn shared_state_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n=== Shared State (Arc and Mutex) ===")?;
     
     // create thread-safe counter using Arc and Mutex.
     let counter = Arc::new(Mutex::new(0));
+    let log = ThreadLog::default();
     let mut handles = vec![];
 
     for i in 0..3 {
         let counter_clone = Arc::clone(&counter);
+        let log = log.clone();
         let handle = thread::spawn(move || {
             let mut num = counter_clone.lock().unwrap();
             *num += 1;
-            println!("Thread {} modified counter to: {}", i, *num);
+            log.line(format!("Thread {} modified counter to: {}", i, *num));
         });
         handles.push(handle);
     }
@@ -68,65 +102,117 @@ fn shared_state_example() {
         handle.join().unwrap();
     }
 
-    println!("Final counter value: {}", *counter.lock().unwrap());
+    log.write_to(out)?;
+    writeln!(out, "Final counter value: {}", *counter.lock().unwrap())?;
+
+    Ok(())
 }
 
-fn rwlock_example() {
-    println!("\n=== RwLock Example ===");
+/// Lets several readers and one writer share a vector behind an `RwLock`.
+pub fn rwlock_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n=== RwLock Example ===")?;
     
     let data = Arc::new(RwLock::new(vec![1, 2, 3, 4]));
+    let log = ThreadLog::default();
     let mut handles = vec![];
 
     // spawn reader threads.
     for i in 0..2 {
         let data_clone = Arc::clone(&data);
+        let log = log.clone();
         handles.push(thread::spawn(move || {
             // Multiple threads can read simultaneously
             let values = data_clone.read().unwrap();
-            println!("Reader {} sees: {:?}", i, *values);
+            log.line(format!("Reader {} sees: {:?}", i, *values));
         }));
     }
 
     // spawn writer thread.
     let data_clone = Arc::clone(&data);
+    let writer_log = log.clone();
     handles.push(thread::spawn(move || {
         let mut values = data_clone.write().unwrap();
         values.push(5);
-        println!("Writer modified data to: {:?}", *values);
+        writer_log.line(format!("Writer modified data to: {:?}", *values));
     }));
 
     for handle in handles {
         handle.join().unwrap();
     }
+
+    log.write_to(out)
 }
 
-fn threadpool_example() {
-    println!("\n=== ThreadPool Example ===");
+/// Runs `jobs` jobs on a `ThreadPool` of `threads` workers and collects their results.
+pub fn threadpool_example(threads: usize, jobs: usize, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n=== ThreadPool Example ===")?;
     
-    // create a thread pool with 3 worker threads.
-    let pool = ThreadPool::new(3);
+    // create a thread pool with a fixed number of worker threads.
+    let pool = ThreadPool::new(threads);
     let shared_data = Arc::new(Mutex::new(vec![]));
+    let log = ThreadLog::default();
 
-    // Submit 5 jobs to the pool
-    for i in 0..5 {
+    // Submit the jobs to the pool
+    for i in 0..jobs {
         let data_clone = Arc::clone(&shared_data);
+        let log = log.clone();
         pool.execute(move || {
             thread::sleep(Duration::from_millis(100));
             let mut data = data_clone.lock().unwrap();
             data.push(i);
-            println!("Job {} completed by thread {:?}", i, thread::current().id());
+            log.line(format!("Job {} completed by thread {:?}", i, thread::current().id()));
         });
     }
 
     pool.join();
-    println!("All jobs completed. Final data: {:?}", *shared_data.lock().unwrap());
+    log.write_to(out)?;
+    writeln!(out, "All jobs completed. Final data: {:?}", *shared_data.lock().unwrap())?;
+
+    Ok(())
 }
 
-pub fn demo() {
-    basic_threads_example();
-    channel_example();
+/// Runs every concurrency example in order.
+pub fn demo(threads: usize, jobs: usize, out: &mut dyn Write) -> io::Result<()> {
+    basic_threads_example(out)?;
+    channel_example(out)?;
+
+    shared_state_example(out)?;
+    rwlock_example(out)?;
+    threadpool_example(threads, jobs, out)?;
+
+    Ok(())
+}
 
-    shared_state_example();
-    rwlock_example();
-    threadpool_example();
-}
\ No newline at end of file
+/// Knobs for the thread pool example.
+const PARAMS: &[Param] = &[
+    Param {
+        name: "threads",
+        help: "Worker threads in the thread pool",
+        kind: ParamKind::Integer { min: 1, max: 64 },
+        default: "3",
+    },
+    Param {
+        name: "jobs",
+        help: "Jobs submitted to the thread pool",
+        kind: ParamKind::Integer { min: 1, max: 10_000 },
+        default: "5",
+    },
+];
+
+/// Registry entry for the `concurrency` demo.
+pub const DEMO: Entry = Entry {
+    name: "concurrency",
+    category: Category::Advanced,
+    summary: "Threads, channels, `Arc<Mutex>` shared state, `RwLock` and a thread pool.",
+    prerequisites: &["lifetimes", "traits"],
+    params: PARAMS,
+    masks: &[
+        Mask::Unordered,
+        Mask::After("modified counter to: "),
+        Mask::After("sees: "),
+        Mask::After("completed by thread "),
+        Mask::After("Final data: "),
+    ],
+    run: |params, out| demo(params.count("threads"), params.count("jobs"), out),
+    ..Entry::DEFAULTS
+};
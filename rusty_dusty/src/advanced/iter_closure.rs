@@ -1,16 +1,30 @@
-fn closure_basics() {
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
+use crate::exercise::Exercise;
+
+/// Asserts that two expressions are equal, like `assert_eq!`, and reports the
+/// passed check to the demo output.
+macro_rules! check_eq {
+    ($out:expr, $left:expr, $right:expr) => {{
+        assert_eq!($left, $right);
+        writeln!($out, "assert passed: {} == {}", stringify!($left), stringify!($right))
+    }};
+}
+
+fn closure_basics(out: &mut dyn Write) -> io::Result<()> {
     // basic closure.
     let add = |x, y| x + y;
-    assert_eq!(add(2, 3), 5);
+    check_eq!(out, add(2, 3), 5)?;
 
     // closure capturing environment.
     let multiplier = 2;
     let multiply = |x| x * multiplier;
-    assert_eq!(multiply(5), 10);
+    check_eq!(out, multiply(5), 10)?;
 
     // closure with explicit type annotations.
     let typed_closure: fn(i32) -> i32 = |x| x * x;
-    assert_eq!(typed_closure(4), 16);
+    check_eq!(out, typed_closure(4), 16)?;
 
     // mutable closure.
     let mut counter = 0;
@@ -18,11 +32,13 @@ fn closure_basics() {
         counter += 1;
         counter
     };
-    assert_eq!(increment(), 1);
-    assert_eq!(increment(), 2);
+    check_eq!(out, increment(), 1)?;
+    check_eq!(out, increment(), 2)?;
+
+    Ok(())
 }
 
-fn closure_traits() {
+fn closure_traits(out: &mut dyn Write) -> io::Result<()> {
     // fn trait - no capture.
     let _add: fn(i32, i32) -> i32 = |x, y| x + y;
     
@@ -30,18 +46,21 @@ fn closure_traits() {
     let mut vec = vec![1, 2, 3];
     let mut mutate = || vec.push(4);
     mutate();
-    assert_eq!(vec, vec![1, 2, 3, 4]);
+    check_eq!(out, vec, vec![1, 2, 3, 4])?;
     
     // fnonce trait - takes ownership.
+    #[allow(clippy::useless_vec)]
     let vec = vec![1, 2, 3];
     let consume = move || {
         let sum: i32 = vec.iter().sum();
         sum
     };
-    assert_eq!(consume(), 6);
+    check_eq!(out, consume(), 6)?;
+
+    Ok(())
 }
 
-fn iterator_creation() {    
+fn iterator_creation(out: &mut dyn Write) -> io::Result<()> {    
     // creating custom iterator.
     struct CountTo(u32);
     
@@ -60,58 +79,67 @@ fn iterator_creation() {
     
     let count = CountTo(3);
     let collected: Vec<u32> = count.collect();
-    assert_eq!(collected, vec![3, 2, 1]);
+    check_eq!(out, collected, vec![3, 2, 1])?;
+
+    Ok(())
 }
 
-fn iterator_operations() {
+fn iterator_operations(out: &mut dyn Write) -> io::Result<()> {
+    #[allow(clippy::useless_vec)]
     let numbers = vec![1, 2, 3, 4, 5];
     
     // map
     let doubled: Vec<i32> = numbers.iter()
         .map(|x| x * 2)
         .collect();
-    assert_eq!(doubled, vec![2, 4, 6, 8, 10]);
+    check_eq!(out, doubled, vec![2, 4, 6, 8, 10])?;
     
     // filter
     let even: Vec<&i32> = numbers.iter()
         .filter(|x| *x % 2 == 0)
         .collect();
-    assert_eq!(even, vec![&2, &4]);
+    check_eq!(out, even, vec![&2, &4])?;
     
     // fold
+    #[allow(clippy::unnecessary_fold)]
     let sum = numbers.iter()
         .fold(0, |acc, x| acc + x);
-    assert_eq!(sum, 15);
+    check_eq!(out, sum, 15)?;
     
     // chain
+    #[allow(clippy::useless_vec)]
     let more_numbers = vec![6, 7];
     let combined: Vec<&i32> = numbers.iter()
         .chain(more_numbers.iter())
         .collect();
-    assert_eq!(combined, vec![&1, &2, &3, &4, &5, &6, &7]);
+    check_eq!(out, combined, vec![&1, &2, &3, &4, &5, &6, &7])?;
     
     // enumerate
     let enumerated: Vec<(usize, &i32)> = numbers.iter()
         .enumerate()
         .collect();
-    assert_eq!(enumerated[0], (0, &1));
+    check_eq!(out, enumerated[0], (0, &1))?;
+
+    Ok(())
 }
 
-fn advanced_iterators() {
+fn advanced_iterators(out: &mut dyn Write) -> io::Result<()> {
     // zip
     let xs = vec![1, 2, 3];
     let ys = vec![4, 5, 6];
+    #[allow(clippy::useless_conversion)]
     let zipped: Vec<(i32, i32)> = xs.into_iter()
         .zip(ys.into_iter())
         .collect();
-    assert_eq!(zipped, vec![(1, 4), (2, 5), (3, 6)]);
+    check_eq!(out, zipped, vec![(1, 4), (2, 5), (3, 6)])?;
     
     // flat_map
     let nested = vec![vec![1, 2], vec![3, 4]];
+    #[allow(clippy::flat_map_identity)]
     let flattened: Vec<i32> = nested.into_iter()
         .flat_map(|x| x)
         .collect();
-    assert_eq!(flattened, vec![1, 2, 3, 4]);
+    check_eq!(out, flattened, vec![1, 2, 3, 4])?;
     
     // take and skip
     let numbers: Vec<i32> = (1..=10).collect();
@@ -119,19 +147,41 @@ fn advanced_iterators() {
         .skip(2)
         .take(3)
         .collect();
-    assert_eq!(subset, vec![3, 4, 5]);
+    check_eq!(out, subset, vec![3, 4, 5])?;
+
+    Ok(())
 }
 
 // demo function that showcases all the functionality
-pub fn demo() {
-    println!("\nRunning closure examples...");
-    closure_basics();
-    closure_traits();
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\nRunning closure examples...")?;
+    closure_basics(out)?;
+    closure_traits(out)?;
     
-    println!("\nRunning iterator examples...");
-    iterator_creation();
-    iterator_operations();
-    advanced_iterators();
+    writeln!(out, "\nRunning iterator examples...")?;
+    iterator_creation(out)?;
+    iterator_operations(out)?;
+    advanced_iterators(out)?;
     
-    println!("\nAll examples completed successfully!\n");
-}
\ No newline at end of file
+    writeln!(out, "\nAll examples completed successfully!\n")?;
+
+    Ok(())
+}
+
+/// Exercises on this topic; the stubs live in `exercises/`.
+const EXERCISES: &[Exercise] = &[Exercise {
+    name: "iter_pipeline",
+    summary: "Replace loops with iterator adapters and return closures.",
+    tests: include_str!("../checks/iter_pipeline.rs"),
+}];
+
+/// Registry entry for the `iter_closure` demo.
+pub const DEMO: Entry = Entry {
+    name: "iter_closure",
+    category: Category::Advanced,
+    summary: "Closures, the `Fn` traits, custom iterators and iterator adaptors.",
+    prerequisites: &["vector", "traits"],
+    exercises: EXERCISES,
+    run: |_, out| demo(out),
+    ..Entry::DEFAULTS
+};
@@ -1,2 +1,12 @@
+use crate::demo::Demo;
+
 pub mod concurrency;
-pub mod iter_closure;
\ No newline at end of file
+pub mod iter_closure;
+
+/// The advanced topic demos.
+pub fn demos() -> &'static [&'static dyn Demo] {
+    &[
+        &iter_closure::DEMO,
+        &concurrency::DEMO,
+    ]
+}
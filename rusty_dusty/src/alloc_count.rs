@@ -0,0 +1,180 @@
+//! # Allocation Counting
+//!
+//! [`CountingAllocator`] forwards to the system allocator and counts every
+//! allocation made and how many bytes it asked for, so demos can show what an
+//! ownership strategy costs instead of describing it. Types that want their
+//! clones counted too call [`record_clone`] from their `Clone` impl.
+//!
+//! The library leaves the choice of allocator to the program using it. The
+//! `rusty_dusty` binary and the tests that check counts install it with
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static GLOBAL: CountingAllocator = CountingAllocator;
+//! ```
+//!
+//! and without it every allocation count stays at zero.
+//!
+//! The counts are kept per thread: tests run in parallel, and a measurement
+//! should only see the work of the code it wraps.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::ops::AddAssign;
+
+/// The system allocator, counting allocations as it goes.
+pub struct CountingAllocator;
+
+// The library's own tests count allocations too
+#[cfg(test)]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+thread_local! {
+    // `const` initialization keeps the counters from allocating on first use
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static BYTES: Cell<usize> = const { Cell::new(0) };
+    static CLONES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn bump(counter: &'static std::thread::LocalKey<Cell<usize>>, by: usize) {
+    // Fails only while the thread is being torn down, when nothing is measured
+    let _ = counter.try_with(|count| count.set(count.get() + by));
+}
+
+fn record(size: usize) {
+    bump(&ALLOCATIONS, 1);
+    bump(&BYTES, size);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    /// Counts as one allocation of the new size, as a fresh allocation and copy would.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Counts one call to an instrumented `Clone` impl on the current thread.
+pub fn record_clone() {
+    bump(&CLONES, 1);
+}
+
+/// What a stretch of code cost on the current thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub allocations: usize,
+    pub bytes: usize,
+    /// Calls to `Clone` impls that report through [`record_clone`].
+    pub clones: usize,
+}
+
+impl Usage {
+    /// The totals for the current thread so far.
+    pub fn current() -> Usage {
+        Usage {
+            allocations: ALLOCATIONS.with(Cell::get),
+            bytes: BYTES.with(Cell::get),
+            clones: CLONES.with(Cell::get),
+        }
+    }
+
+    fn since(self, before: Usage) -> Usage {
+        Usage {
+            allocations: self.allocations - before.allocations,
+            bytes: self.bytes - before.bytes,
+            clones: self.clones - before.clones,
+        }
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        self.allocations += other.allocations;
+        self.bytes += other.bytes;
+        self.clones += other.clones;
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} bytes, {} clones",
+            self.allocations, self.bytes, self.clones
+        )
+    }
+}
+
+/// Runs `f` and returns its result along with what it cost.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let before = Usage::current();
+    let result = f();
+    (result, Usage::current().since(before))
+}
+
+/// Runs `f` and returns its result along with the allocations it made.
+///
+/// ```
+/// use rusty_dusty::alloc_count::{self, CountingAllocator};
+///
+/// #[global_allocator]
+/// static GLOBAL: CountingAllocator = CountingAllocator;
+///
+/// let (text, allocations) = alloc_count::count(|| "hello".to_string());
+/// assert_eq!((text.as_str(), allocations), ("hello", 1));
+/// ```
+pub fn count<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let (result, usage) = measure(f);
+    (result, usage.allocations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_allocations() {
+        let (_, allocations) = count(|| Box::new(1u64));
+        assert_eq!(allocations, 1);
+
+        let text = "satellite".to_string();
+        let (copies, allocations) = count(|| vec![text.clone(); 3]);
+        assert_eq!((copies.len(), allocations), (3, 4));
+
+        let ((), allocations) = count(|| drop(copies));
+        assert_eq!(allocations, 0);
+    }
+
+    #[test]
+    fn test_measures_bytes_and_clones() {
+        let ((), usage) = measure(|| {
+            let _buffer: Vec<u8> = Vec::with_capacity(100);
+            record_clone();
+        });
+        assert_eq!(
+            usage,
+            Usage {
+                allocations: 1,
+                bytes: 100,
+                clones: 1
+            }
+        );
+        assert_eq!(usage.to_string(), "1 allocations, 100 bytes, 1 clones");
+    }
+}
@@ -0,0 +1,38 @@
+#[test]
+fn parse_age_trims_whitespace() {
+    assert_eq!(parse_age(" 42 "), Ok(42));
+}
+
+#[test]
+fn parse_age_rejects_empty_input() {
+    assert_eq!(parse_age("   "), Err(AgeError::Empty));
+}
+
+#[test]
+fn parse_age_wraps_parse_errors() {
+    assert!(matches!(parse_age("forty"), Err(AgeError::NotANumber(_))));
+    assert!(matches!(parse_age("-3"), Err(AgeError::NotANumber(_))));
+}
+
+#[test]
+fn parse_age_rejects_ages_over_150() {
+    assert_eq!(parse_age("150"), Ok(150));
+    assert_eq!(parse_age("151"), Err(AgeError::TooOld(151)));
+}
+
+#[test]
+fn sum_all_adds_numbers() {
+    assert_eq!(sum_all(&["1", "-2", "30"]), Ok(29));
+    assert_eq!(sum_all(&[]), Ok(0));
+}
+
+#[test]
+fn sum_all_stops_at_bad_input() {
+    assert!(sum_all(&["1", "two", "3"]).is_err());
+}
+
+#[test]
+fn parse_ages_returns_first_error() {
+    assert_eq!(parse_ages(&["1", "2"]), Ok(vec![1, 2]));
+    assert_eq!(parse_ages(&["1", "", "200"]), Err(AgeError::Empty));
+}
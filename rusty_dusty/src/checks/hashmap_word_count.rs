@@ -0,0 +1,38 @@
+#[test]
+fn word_count_counts_each_word() {
+    let counts = word_count("the cat saw the dog");
+    assert_eq!(counts.get("the"), Some(&2));
+    assert_eq!(counts.get("cat"), Some(&1));
+    assert_eq!(counts.len(), 4);
+}
+
+#[test]
+fn word_count_ignores_case_and_extra_whitespace() {
+    let counts = word_count("  Rust rust\tRUST\n");
+    assert_eq!(counts.get("rust"), Some(&3));
+    assert_eq!(counts.len(), 1);
+}
+
+#[test]
+fn word_count_of_empty_text_is_empty() {
+    assert!(word_count("").is_empty());
+}
+
+#[test]
+fn most_common_picks_highest_count() {
+    assert_eq!(most_common("a b b c b a"), Some(("b".to_string(), 3)));
+}
+
+#[test]
+fn most_common_breaks_ties_alphabetically() {
+    assert_eq!(most_common("pear apple pear apple"), Some(("apple".to_string(), 2)));
+    assert_eq!(most_common(""), None);
+}
+
+#[test]
+fn group_by_initial_keeps_order() {
+    let groups = group_by_initial(&["apple", "bean", "avocado", "beet"]);
+    assert_eq!(groups[&'a'], ["apple", "avocado"]);
+    assert_eq!(groups[&'b'], ["bean", "beet"]);
+    assert_eq!(groups.len(), 2);
+}
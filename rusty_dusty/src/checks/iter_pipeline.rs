@@ -0,0 +1,25 @@
+#[test]
+fn squares_of_odds_keeps_order() {
+    assert_eq!(squares_of_odds(&[1, 2, 3, 4, 5]), [1, 9, 25]);
+    assert_eq!(squares_of_odds(&[-3]), [9]);
+    assert!(squares_of_odds(&[2, 4]).is_empty());
+}
+
+#[test]
+fn make_adder_captures_n() {
+    let add_five = make_adder(5);
+    assert_eq!(add_five(1), 6);
+    assert_eq!(add_five(-5), 0);
+}
+
+#[test]
+fn apply_n_repeats_the_closure() {
+    assert_eq!(apply_n(|x| x * 2, 3, 1), 8);
+    assert_eq!(apply_n(make_adder(1), 0, 7), 7);
+}
+
+#[test]
+fn total_long_word_length_skips_short_words() {
+    assert_eq!(total_long_word_length(&["a", "tree", "is", "green"], 2), 9);
+    assert_eq!(total_long_word_length(&[], 0), 0);
+}
@@ -0,0 +1,34 @@
+#[test]
+fn first_even_skips_odd_numbers() {
+    assert_eq!(first_even(&[1, 3, 4, 6]), Some(4));
+}
+
+#[test]
+fn first_even_of_no_evens_is_none() {
+    assert_eq!(first_even(&[1, 3, 5]), None);
+    assert_eq!(first_even(&[]), None);
+}
+
+#[test]
+fn checked_divide_divides() {
+    assert_eq!(checked_divide(10, 3), Some(3));
+    assert_eq!(checked_divide(-8, 2), Some(-4));
+}
+
+#[test]
+fn checked_divide_by_zero_is_none() {
+    assert_eq!(checked_divide(1, 0), None);
+}
+
+#[test]
+fn name_length_defaults_to_zero() {
+    assert_eq!(name_length(Some("Ferris")), 6);
+    assert_eq!(name_length(None), 0);
+}
+
+#[test]
+fn initial_is_upper_case() {
+    assert_eq!(initial(Some("ferris")), Some('F'));
+    assert_eq!(initial(Some("")), None);
+    assert_eq!(initial(None), None);
+}
@@ -1,14 +1,20 @@
 use std::collections::BTreeMap;
+use std::io::{self, Write};
 
-pub fn demo(){
-    println!("BTreeMaps");
+use crate::demo::{Category, Entry};
+use crate::params::{Param, ParamKind};
 
-    btreemap_basics();
-    btreemap_advanced();
-    btreemap_performance();
+pub fn demo(inserts: i32, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BTreeMaps")?;
+
+    btreemap_basics(out)?;
+    btreemap_advanced(out)?;
+    btreemap_performance(inserts, out)?;
+
+    Ok(())
 }
 
-fn btreemap_basics() {
+fn btreemap_basics(out: &mut dyn Write) -> io::Result<()> {
     let mut map: BTreeMap<i32, String> = BTreeMap::new();
     
     map.insert(1, String::from("one"));
@@ -18,11 +24,13 @@ fn btreemap_basics() {
     map.remove(&1);
 
     for (key, value) in &map {
-        println!("{}: {}", key, value);
+        writeln!(out, "{}: {}", key, value)?;
     }
+
+    Ok(())
 }
 
-fn btreemap_advanced() {
+fn btreemap_advanced(out: &mut dyn Write) -> io::Result<()> {
     let mut map = BTreeMap::new();
     
     map.insert(1, "One");
@@ -30,23 +38,46 @@ fn btreemap_advanced() {
     map.insert(3, "Three");
     
     for (key, value) in map.range(1..3) {
-        println!("{}: {}", key, value);
+        writeln!(out, "{}: {}", key, value)?;
     }
     
     if let Some((key, value)) = map.first_key_value() {
-        println!("First: {}: {}", key, value);
+        writeln!(out, "First: {}: {}", key, value)?;
     }
+
+    Ok(())
 }
 
-fn btreemap_performance() {
+fn btreemap_performance(inserts: i32, out: &mut dyn Write) -> io::Result<()> {
     let mut map = BTreeMap::new();
     
-    for i in 0..1000 {
+    for i in 0..inserts {
         map.insert(i, i.to_string());
     }
     
     let range_sum: i32 = map.range(100..200)
                            .map(|(k, _)| k)
                            .sum();
-    println!("Range sum: {}\n", range_sum);
+    writeln!(out, "Range sum: {}\n", range_sum)?;
+
+    Ok(())
 }
+
+/// Knobs for the performance example.
+const PARAMS: &[Param] = &[Param {
+    name: "inserts",
+    help: "Keys inserted before the range query",
+    kind: ParamKind::Integer { min: 1, max: 1_000_000 },
+    default: "1000",
+}];
+
+/// Registry entry for the `btreemap` demo.
+pub const DEMO: Entry = Entry {
+    name: "btreemap",
+    category: Category::Collections,
+    summary: "Ordered maps with `BTreeMap`: range queries and first/last access.",
+    prerequisites: &["hashmap"],
+    params: PARAMS,
+    run: |params, out| demo(params.integer("inserts") as i32, out),
+    ..Entry::DEFAULTS
+};
@@ -1,14 +1,19 @@
-use std::{collections::HashMap, hash::{Hash, Hasher}};
+use std::{collections::HashMap, hash::{Hash, Hasher}, io::{self, Write}};
 
-pub fn demo(){
-    println!("Hashmaps");
+use crate::demo::{Category, Entry, Mask};
+use crate::exercise::Exercise;
 
-    hashmap_basics();
-    hashmap_advanced();
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Hashmaps")?;
+
+    hashmap_basics(out)?;
+    hashmap_advanced(out)?;
     hashmap_optimization();
+
+    Ok(())
 }
 
-fn hashmap_basics() {
+fn hashmap_basics(out: &mut dyn Write) -> io::Result<()> {
     let mut map: HashMap<String, i32> = HashMap::new();
     
     map.insert(String::from("one"), 1);
@@ -18,11 +23,13 @@ fn hashmap_basics() {
     map.remove("one");
 
     if map.contains_key("two") {
-        println!("Contains key 'two'");
+        writeln!(out, "Contains key 'two'")?;
     }
+
+    Ok(())
 }
 
-fn hashmap_advanced() {
+fn hashmap_advanced(out: &mut dyn Write) -> io::Result<()> {
     let mut map = HashMap::new();
     
     map.entry(String::from("Key")).or_insert(0);
@@ -33,9 +40,11 @@ fn hashmap_advanced() {
     }
     
     for (key, value) in map.drain() {
-        println!("{}: {}", key, value);
+        writeln!(out, "{}: {}", key, value)?;
     }
-    println!()
+    writeln!(out)?;
+
+    Ok(())
 }
 
 // demonstrates custom types in hashmaps
@@ -70,4 +79,27 @@ fn hashmap_optimization() {
         (String::from("a"), 1),
         (String::from("b"), 2),
     ]);
+
+    // custom key types only need `Eq` and `Hash`.
+    let mut _by_key: HashMap<CustomKey, &str> = HashMap::new();
+    _by_key.insert(CustomKey { id: 1, name: String::from("one") }, "first");
 }
+
+/// Exercises on this topic; the stubs live in `exercises/`.
+const EXERCISES: &[Exercise] = &[Exercise {
+    name: "hashmap_word_count",
+    summary: "Count and group words with `HashMap` and the entry API.",
+    tests: include_str!("../checks/hashmap_word_count.rs"),
+}];
+
+/// Registry entry for the `hashmap` demo.
+pub const DEMO: Entry = Entry {
+    name: "hashmap",
+    category: Category::Collections,
+    summary: "`HashMap` basics, the entry API, custom keys and capacity tuning.",
+    prerequisites: &["vector"],
+    exercises: EXERCISES,
+    masks: &[Mask::Unordered],
+    run: |_, out| demo(out),
+    ..Entry::DEFAULTS
+};
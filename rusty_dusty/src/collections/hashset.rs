@@ -1,23 +1,30 @@
 use std::collections::HashSet;
+use std::io::{self, Write};
 
-pub fn demo(){
-    println!("Hashsets");
+use crate::demo::{Category, Entry};
 
-    hashset_basics();
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Hashsets")?;
+
+    hashset_basics(out)?;
     hashset_operations();
     hashset_optimization();
+
+    Ok(())
 }
 
-fn hashset_basics() {
+fn hashset_basics(out: &mut dyn Write) -> io::Result<()> {
     let mut set: HashSet<i32> = HashSet::new();
     
     set.insert(1);
     set.insert(2);
     
     if set.contains(&1) {
-        println!("Contains 1");
+        writeln!(out, "Contains 1")?;
     }
     set.remove(&1);
+
+    Ok(())
 }
 
 fn hashset_operations() {
@@ -40,4 +47,14 @@ fn hashset_optimization() {
     set.reserve(1000);
     
     set.extend(vec![1, 2, 3, 4, 5]);
-}
\ No newline at end of file
+}
+
+/// Registry entry for the `hashset` demo.
+pub const DEMO: Entry = Entry {
+    name: "hashset",
+    category: Category::Collections,
+    summary: "`HashSet` membership and set operations: union, intersection and difference.",
+    prerequisites: &["hashmap"],
+    run: |_, out| demo(out),
+    ..Entry::DEFAULTS
+};
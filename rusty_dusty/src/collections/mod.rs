@@ -1,4 +1,16 @@
+use crate::demo::Demo;
+
 pub mod btreemap;
 pub mod hashmap;
 pub mod hashset;
-pub mod vector;
\ No newline at end of file
+pub mod vector;
+
+/// The standard collection demos.
+pub fn demos() -> &'static [&'static dyn Demo] {
+    &[
+        &vector::DEMO,
+        &hashmap::DEMO,
+        &btreemap::DEMO,
+        &hashset::DEMO,
+    ]
+}
@@ -1,31 +1,41 @@
-pub fn demo() {
-    println!("Vectors");
+use std::io::{self, Write};
 
-    vector_initialization_examples();
-    vector_operations();
+use crate::demo::{Category, Entry};
+
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Vectors")?;
+
+    vector_initialization_examples(out)?;
+    vector_operations(out)?;
     
-    advanced_vector_features();
-    vector_safety_and_optimization();
+    advanced_vector_features(out)?;
+    vector_safety_and_optimization(out)?;
+
+    Ok(())
 }
 
-fn vector_initialization_examples() {
+fn vector_initialization_examples(out: &mut dyn Write) -> io::Result<()> {
     let mut _empty_vec: Vec<i32> = Vec::new();
     let mut _with_capacity: Vec<i32> = Vec::with_capacity(10);
+    #[allow(clippy::useless_vec)]
     let _macro_vec = vec![1, 2, 3, 4, 5];
+    #[allow(clippy::useless_vec)]
     let _filled_vec = vec![0; 10];
 
     let tuple_vector = vec![(1, 2), (3, 4), (5, 6)];
-    println!("Tuple Vector: {:?}", tuple_vector);
+    writeln!(out, "Tuple Vector: {:?}", tuple_vector)?;
+
+    Ok(())
 }
 
-fn vector_operations() {
+fn vector_operations(out: &mut dyn Write) -> io::Result<()> {
     let mut vec = Vec::new();
     
     vec.push(1);
     vec.extend([2, 3, 4].iter().cloned());
     
     if let Some(last) = vec.pop() {
-        println!("Popped: {}", last);
+        writeln!(out, "Popped: {}", last)?;
     }
     
     // panics if out of bounds
@@ -36,17 +46,19 @@ fn vector_operations() {
     vec.remove(1);
     
     let slice = &vec[1..3];
-    println!("Slice: {:?}", slice);
+    writeln!(out, "Slice: {:?}", slice)?;
     
     vec.reserve(10);
     vec.shrink_to_fit();
+
+    Ok(())
 }
 
-fn advanced_vector_features() {
+fn advanced_vector_features(out: &mut dyn Write) -> io::Result<()> {
     let mut vec = vec![1, 2, 3, 4, 5];
     
     let drained: Vec<_> = vec.drain(1..3).collect();
-    println!("Drained: {:?}", drained);
+    writeln!(out, "Drained: {:?}", drained)?;
     
     vec.retain(|&x| x % 2 == 0);
     vec.dedup();
@@ -54,15 +66,17 @@ fn advanced_vector_features() {
     // let (_left, _right) = vec.split_at(2);
     
     for window in vec.windows(2) {
-        println!("Window: {:?}", window);
+        writeln!(out, "Window: {:?}", window)?;
     }
     
     for chunk in vec.chunks(2) {
-        println!("Chunk: {:?}", chunk);
+        writeln!(out, "Chunk: {:?}", chunk)?;
     }
+
+    Ok(())
 }
 
-fn vector_safety_and_optimization() {
+fn vector_safety_and_optimization(out: &mut dyn Write) -> io::Result<()> {
     let mut vec = Vec::with_capacity(1000);
     vec.reserve_exact(1000);
     
@@ -76,5 +90,17 @@ fn vector_safety_and_optimization() {
     
     // zero-cost abstractions
     let sum: i32 = vec.iter().sum();
-    println!("Sum: {}\n", sum);
-}
\ No newline at end of file
+    writeln!(out, "Sum: {}\n", sum)?;
+
+    Ok(())
+}
+
+/// Registry entry for the `vector` demo.
+pub const DEMO: Entry = Entry {
+    name: "vector",
+    category: Category::Collections,
+    summary: "`Vec` initialization, operations, draining, windows and capacity management.",
+    prerequisites: &["own"],
+    run: |_, out| demo(out),
+    ..Entry::DEFAULTS
+};
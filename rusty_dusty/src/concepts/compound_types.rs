@@ -1,90 +1,132 @@
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
+
+pub mod effects;
+pub mod encounter;
+pub mod inventory;
+pub mod progression;
+pub mod world;
+
+use effects::Effects;
+use encounter::{Encounter, Outcome, Side};
+use inventory::{Inventory, Item};
+use world::World;
+
 // Type alias for clarity and reusability
-type Health = u32;
-type Mana = u32;
+pub type Health = u32;
+pub type Mana = u32;
+// Characters are addressed by their index in an `Encounter`
+pub type CharacterId = usize;
 
 // Enum to represent character classes
-#[derive(Debug, PartialEq)]
-enum CharacterClass {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterClass {
     Warrior,
     Mage,
     Rogue,
 }
 
 // Enum to represent character actions with associated data
-#[derive(Debug)]
-enum Action {
-    Attack { damage: Health, target: String },
-    CastSpell { spell_name: String, mana_cost: Mana },
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Attack { damage: Health, target: CharacterId },
+    // Spells with a lasting effect land on `target`, or on the caster if none
+    CastSpell { spell_name: String, mana_cost: Mana, target: Option<CharacterId> },
     Dodge,
     Heal { amount: Health },
+    // Drink a potion or equip a weapon or armor from the inventory
+    UseItem { item: String },
 }
 
 // Struct to represent a game character
-#[derive(Debug)]
-struct Character {
-    name: String,
-    health: Health,
-    mana: Mana,
-    class: CharacterClass,
-    level: u32,
+#[derive(Debug, Clone)]
+pub struct Character {
+    pub name: String,
+    pub health: Health,
+    pub max_health: Health,
+    pub mana: Mana,
+    pub max_mana: Mana,
+    pub class: CharacterClass,
+    pub level: u32,
+    // Experience towards the next level
+    pub xp: u32,
+    // Where the character stands on the world map
+    pub position: Position,
+    pub inventory: Inventory,
+    pub effects: Effects,
 }
 
 // Unit struct for default configuration
 #[derive(Debug)]
-struct DefaultCharacterConfig;
+pub struct DefaultCharacterConfig;
 
 // Tuple struct for position in game world
-#[derive(Debug, Clone, PartialEq)]
-struct Position(i32, i32);
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Position(pub i32, pub i32);
 
 // Implementation for Character
 impl Character {
     // Associated function to create a new character
-    fn new(name: String, class: CharacterClass, level: u32) -> Self {
-        let (health, mana) = match class {
-            CharacterClass::Warrior => (100, 20),
-            CharacterClass::Mage => (60, 80),
-            CharacterClass::Rogue => (80, 40),
-        };
+    // Stats come from the class table and grow with the starting level
+    pub fn new(name: String, class: CharacterClass, level: u32) -> Self {
+        let stats = progression::stats(class);
+        let (max_health, max_mana) = (stats.max_health(level), stats.max_mana(level));
         Character {
             name,
-            health,
-            mana,
+            health: max_health,
+            max_health,
+            mana: max_mana,
+            max_mana,
             class,
             level,
+            xp: 0,
+            position: Position::default(),
+            inventory: Inventory::default(),
+            effects: Effects::default(),
         }
     }
 
-    // Method to perform an action
-    fn perform_action(&mut self, action: Action) -> String {
+    // Method to perform an action. Only the acting character is affected; an
+    // `Encounter` also applies the effect to the target
+    pub fn perform_action(&mut self, action: Action) -> String {
         match action {
             Action::Attack { damage, target } => {
                 if self.health > 0 {
                     self.health = self.health.saturating_sub(10); // Simulate stamina cost
-                    format!("{} attacks {} for {} damage!", self.name, target, damage)
+                    let damage = self.attack_damage(damage);
+                    format!("{} attacks character {} for {} damage!", self.name, target, damage)
                 } else {
                     format!("{} is too weak to attack!", self.name)
                 }
             }
-            Action::CastSpell { spell_name, mana_cost } => {
+            Action::CastSpell { spell_name, mana_cost, target } => {
                 if self.mana >= mana_cost {
                     self.mana -= mana_cost;
-                    format!("{} casts {}!", self.name, spell_name)
+                    match (effects::spell(&spell_name), target) {
+                        (_, Some(target)) => format!("{} casts {} at character {}!", self.name, spell_name, target),
+                        (Some(spell), None) => {
+                            let effect = self.apply_effect(spell.effect, spell.turns);
+                            format!("{} casts {}! {}", self.name, spell_name, effect)
+                        }
+                        (None, None) => format!("{} casts {}!", self.name, spell_name),
+                    }
                 } else {
                     format!("{} lacks mana to cast {}!", self.name, spell_name)
                 }
             }
             Action::Dodge => format!("{} dodges an attack!", self.name),
             Action::Heal { amount } => {
-                self.health = (self.health + amount).min(100);
+                self.health = self.health.saturating_add(amount).min(self.max_health);
                 format!("{} heals for {} health!", self.name, amount)
             }
+            Action::UseItem { item } => self.use_item(&item),
         }
     }
 
-    // Method to move character to a new position
-    fn move_to(&mut self, pos: Position) -> String {
-        format!("{} moves to position ({}, {})", self.name, pos.0, pos.1)
+    // A character is out of the fight once their health reaches zero
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
     }
 }
 
@@ -97,66 +139,119 @@ impl Default for DefaultCharacterConfig {
 
 // Create default character
 impl DefaultCharacterConfig {
-    fn create_default_character(&self, name: String) -> Character {
+    pub fn create_default_character(&self, name: String) -> Character {
         Character::new(name, CharacterClass::Warrior, 1)
     }
 }
 
 // Describe the action
 impl Action {
-    fn describe(&self) -> String {
+    pub fn describe(&self) -> String {
         match self {
-            Action::Attack { damage, target } => format!("Attack dealing {} damage to {}", damage, target),
-            Action::CastSpell { spell_name, mana_cost } => format!("Cast {} costing {} mana", spell_name, mana_cost),
+            Action::Attack { damage, target } => format!("Attack dealing {} damage to character {}", damage, target),
+            Action::CastSpell { spell_name, mana_cost, target: None } => {
+                format!("Cast {} costing {} mana", spell_name, mana_cost)
+            }
+            Action::CastSpell { spell_name, mana_cost, target: Some(target) } => {
+                format!("Cast {} at character {} costing {} mana", spell_name, target, mana_cost)
+            }
             Action::Dodge => "Dodge an incoming attack".to_string(),
             Action::Heal { amount } => format!("Heal for {} health", amount),
+            Action::UseItem { item } => format!("Use {}", item),
         }
     }
 }
 
-pub fn main() {
-    println!("Game Character System\n");
+pub fn main(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Game Character System\n")?;
 
     // Create a character using associated function
     let mut warrior = Character::new("Aragorn".to_string(), CharacterClass::Warrior, 5);
-    println!("Created character: {:?}", warrior);
+    writeln!(out, "Created character: {:?}", warrior)?;
 
     // Create a default character
-    let config = DefaultCharacterConfig::default();
+    let config: DefaultCharacterConfig = Default::default();
     let default_char = config.create_default_character("Grok".to_string());
-    println!("Default character: {:?}", default_char);
+    writeln!(out, "Default character: {:?}", default_char)?;
+
+    // Other classes start with different base stats
+    let mut mage = Character::new("Gandalf".to_string(), CharacterClass::Mage, 7);
+    let mut rogue = Character::new("Bilbo".to_string(), CharacterClass::Rogue, 3);
+    writeln!(out, "Party members: {:?}, {:?}", mage, rogue)?;
 
     // Perform actions
-    let attack = Action::Attack { damage: 20, target: "Orc".to_string() };
-    println!("Action description: {}", attack.describe());
-    println!("Action result: {}", warrior.perform_action(attack));
+    let attack = Action::Attack { damage: 20, target: 1 };
+    writeln!(out, "Action description: {}", attack.describe())?;
+    writeln!(out, "Action result: {}", warrior.perform_action(attack))?;
 
-    let spell = Action::CastSpell { spell_name: "Fireball".to_string(), mana_cost: 30 };
-    println!("Action description: {}", spell.describe());
-    println!("Action result: {}", warrior.perform_action(spell)); // Should fail due to low mana
+    let spell = Action::CastSpell { spell_name: "Fireball".to_string(), mana_cost: 30, target: None };
+    writeln!(out, "Action description: {}", spell.describe())?;
+    writeln!(out, "Action result: {}", warrior.perform_action(spell))?; // Should fail due to low mana
 
     let heal = Action::Heal { amount: 30 };
-    println!("Action result: {}", warrior.perform_action(heal));
+    writeln!(out, "Action result: {}", warrior.perform_action(heal))?;
+
+    // Items go in the inventory, up to what the class can carry
+    let potion = Item::potion("Healing Potion", 40, 1);
+    let gear = [
+        (Item::weapon("Longsword", 6, 8), 1),
+        (Item::armor("Chain Mail", 4, 20), 1),
+        (potion.clone(), 2),
+    ];
+    for (item, count) in gear {
+        writeln!(out, "{}", warrior.pick_up(item, count).expect("a warrior can carry their gear"))?;
+    }
+    writeln!(out, "{}", rogue.pick_up(potion, 1).expect("a rogue can carry a potion"))?;
+    if let Err(err) = mage.pick_up(Item::armor("Plate Armor", 10, 35), 1) {
+        writeln!(out, "{} cannot carry Plate Armor: {}", mage.name, err)?;
+    }
+    for item in ["Longsword", "Chain Mail"] {
+        let equip = Action::UseItem { item: item.to_string() };
+        writeln!(out, "Action description: {}", equip.describe())?;
+        writeln!(out, "Action result: {}", warrior.perform_action(equip))?;
+    }
+    writeln!(
+        out,
+        "{} carries {} of {} weight; attacks deal +{} and hits lose {}",
+        warrior.name,
+        warrior.inventory.weight(),
+        warrior.carry_limit(),
+        warrior.inventory.attack_bonus(),
+        warrior.inventory.defense()
+    )?;
 
-    // Move character
+    // Some spells leave a status effect behind; without a target they land on the caster
+    let barrier = Action::CastSpell { spell_name: "Barrier".to_string(), mana_cost: 20, target: None };
+    writeln!(out, "Action description: {}", barrier.describe())?;
+    writeln!(out, "Action result: {}", mage.perform_action(barrier))?;
+
+    // Move character around the keep; moves are checked against the map
+    let world = World::parse(include_str!("../../files/keep.map"))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    warrior.position = Position(1, 1);
     let new_position = Position(10, 15);
-    println!("{}", warrior.move_to(new_position.clone()));
+    for destination in [new_position, Position(8, 3), Position(12, 3)] {
+        match warrior.move_to(&world, destination) {
+            Ok(report) => writeln!(out, "{}", report)?,
+            Err(err) => writeln!(out, "{} cannot move: {}", warrior.name, err)?,
+        }
+    }
 
     // Pattern matching with enum
     match warrior.class {
-        CharacterClass::Warrior => println!("{} is a mighty warrior!", warrior.name),
-        CharacterClass::Mage => println!("{} is a powerful mage!", warrior.name),
-        CharacterClass::Rogue => println!("{} is a stealthy rogue!", warrior.name),
+        CharacterClass::Warrior => writeln!(out, "{} is a mighty warrior!", warrior.name)?,
+        CharacterClass::Mage => writeln!(out, "{} is a powerful mage!", warrior.name)?,
+        CharacterClass::Rogue => writeln!(out, "{} is a stealthy rogue!", warrior.name)?,
     }
 
     // Destructuring a tuple struct
     let Position(x, y) = new_position;
-    println!("Destructured position: x = {}, y = {}", x, y);
+    writeln!(out, "Destructured position: x = {}, y = {}", x, y)?;
 
     // If let syntax for enum
     let action = Action::Dodge;
     if let Action::Dodge = action {
-        println!("Action is a dodge!");
+        writeln!(out, "Action is a dodge!")?;
     }
 
     // While let loop for level progression
@@ -165,7 +260,65 @@ pub fn main() {
         if new_level >= 10 {
             break;
         }
-        println!("{} is at level {}", warrior.name, new_level);
+        writeln!(out, "{} is at level {}", warrior.name, new_level)?;
         level += 1;
     }
+
+    // Encounter on the keep's map, where actions hit the characters they target
+    writeln!(out, "\n=== Encounter ===")?;
+    let mut encounter = Encounter::on(world.clone());
+    mage.position = Position(3, 7);
+    rogue.position = Position(5, 8);
+    for member in [warrior, mage, rogue] {
+        encounter.join(member, Side::Party);
+    }
+    for (name, class, position) in [
+        ("Orc Brute", CharacterClass::Warrior, Position(9, 7)),
+        ("Goblin Shaman", CharacterClass::Mage, Position(16, 7)),
+    ] {
+        let mut foe = Character::new(name.to_string(), class, 4);
+        foe.position = position;
+        encounter.join(foe, Side::Foes);
+    }
+    let everyone: Vec<&Character> = encounter.combatants().map(|(_, combatant)| &combatant.character).collect();
+    write!(out, "{}", world.render(&everyone))?;
+    let turn_order: Vec<&str> = encounter
+        .turn_order()
+        .into_iter()
+        .filter_map(|id| encounter.character(id))
+        .map(|character| character.name.as_str())
+        .collect();
+    writeln!(out, "Turn order: {}", turn_order.join(", "))?;
+
+    let (outcome, log) = encounter.run(10, &mut encounter::basic_tactic);
+    for line in log {
+        writeln!(out, "{}", line)?;
+    }
+    match outcome {
+        Outcome::Victory(Side::Party) => writeln!(out, "The party is victorious!")?,
+        Outcome::Victory(Side::Foes) => writeln!(out, "The party has fallen...")?,
+        Outcome::Draw => writeln!(out, "Nobody is left standing.")?,
+        Outcome::Ongoing => writeln!(out, "Both sides retreat after {} rounds.", encounter.round())?,
+    }
+
+    // Each character keeps a log of what their status effects did
+    let brute = encounter.combatants().map(|(_, combatant)| &combatant.character).find(|foe| foe.name == "Orc Brute");
+    if let Some(brute) = brute {
+        writeln!(out, "\nEffect log for {}:", brute.name)?;
+        for entry in brute.effects.log() {
+            writeln!(out, "  {}", entry)?;
+        }
+    }
+
+    Ok(())
 }
+
+/// Registry entry for the `compound_types` demo.
+pub const DEMO: Entry = Entry {
+    name: "compound_types",
+    category: Category::Concepts,
+    summary: "Structs, enums with data and type aliases in a small game character system with items, status effects, a tile map and turn-based encounters.",
+    prerequisites: &["tuples"],
+    run: |_, out| main(out),
+    ..Entry::DEFAULTS
+};
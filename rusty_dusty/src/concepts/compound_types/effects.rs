@@ -0,0 +1,350 @@
+//! # Status Effects
+//!
+//! Some spells leave an [`Effect`] behind that lasts for a number of turns:
+//! poison hurts and regeneration heals at the start of each of the character's
+//! turns, a stun costs them their next turn, and a shield soaks up damage from
+//! attacks until it is used up or runs out. The [`SPELLS`] table says which
+//! spell does what.
+//!
+//! Casting an effect on a character who already has it follows that effect's
+//! [`Stacking`] rule, and everything that happens to a character's effects is
+//! kept in their [`Effects::log`].
+
+use super::{Character, Health};
+
+/// A lasting effect and its strength.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Damage taken at the start of each turn.
+    Poison { damage: Health },
+    /// Health regained at the start of each turn.
+    Regeneration { heal: Health },
+    /// The character loses their turns while it lasts.
+    Stun,
+    /// Damage from attacks that the shield takes instead.
+    Shield { absorb: Health },
+}
+
+/// Which effect something is, whatever its strength. A character has at most
+/// one active effect of each kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectKind {
+    Poison,
+    Regeneration,
+    Stun,
+    Shield,
+}
+
+impl EffectKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectKind::Poison => "poison",
+            EffectKind::Regeneration => "regeneration",
+            EffectKind::Stun => "stun",
+            EffectKind::Shield => "shield",
+        }
+    }
+}
+
+/// What happens when an effect lands on a character who already has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// The strengths add up and the longer duration wins.
+    Intensify,
+    /// The stronger effect and the longer duration win.
+    Refresh,
+    /// The new effect is ignored until the old one wears off.
+    Ignore,
+}
+
+impl Effect {
+    pub fn kind(&self) -> EffectKind {
+        match self {
+            Effect::Poison { .. } => EffectKind::Poison,
+            Effect::Regeneration { .. } => EffectKind::Regeneration,
+            Effect::Stun => EffectKind::Stun,
+            Effect::Shield { .. } => EffectKind::Shield,
+        }
+    }
+
+    /// The effect's name, for display.
+    pub fn name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// Poison and shields build up, regeneration only refreshes, and a stun
+    /// cannot be extended while it lasts, so nobody is stunned forever.
+    pub fn stacking(&self) -> Stacking {
+        match self {
+            Effect::Poison { .. } | Effect::Shield { .. } => Stacking::Intensify,
+            Effect::Regeneration { .. } => Stacking::Refresh,
+            Effect::Stun => Stacking::Ignore,
+        }
+    }
+
+    fn strength(&self) -> Health {
+        match *self {
+            Effect::Poison { damage } => damage,
+            Effect::Regeneration { heal } => heal,
+            Effect::Stun => 0,
+            Effect::Shield { absorb } => absorb,
+        }
+    }
+
+    fn with_strength(self, strength: Health) -> Effect {
+        match self {
+            Effect::Poison { .. } => Effect::Poison { damage: strength },
+            Effect::Regeneration { .. } => Effect::Regeneration { heal: strength },
+            Effect::Stun => Effect::Stun,
+            Effect::Shield { .. } => Effect::Shield { absorb: strength },
+        }
+    }
+}
+
+/// A spell that leaves an effect behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spell {
+    pub name: &'static str,
+    pub effect: Effect,
+    /// How many of the target's turns the effect lasts.
+    pub turns: u32,
+}
+
+/// Every spell with a lasting effect. Other spell names only cost mana.
+pub const SPELLS: &[Spell] = &[
+    Spell {
+        name: "Venom",
+        effect: Effect::Poison { damage: 6 },
+        turns: 3,
+    },
+    Spell {
+        name: "Renew",
+        effect: Effect::Regeneration { heal: 8 },
+        turns: 4,
+    },
+    Spell {
+        name: "Hold",
+        effect: Effect::Stun,
+        turns: 1,
+    },
+    Spell {
+        name: "Barrier",
+        effect: Effect::Shield { absorb: 20 },
+        turns: 3,
+    },
+];
+
+/// Looks up a spell by name.
+pub fn spell(name: &str) -> Option<&'static Spell> {
+    SPELLS.iter().find(|spell| spell.name == name)
+}
+
+/// An effect on a character and how long it has left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    pub effect: Effect,
+    pub turns: u32,
+}
+
+/// The effects on a character, and a record of everything that happened to them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Effects {
+    active: Vec<Status>,
+    log: Vec<String>,
+}
+
+impl Effects {
+    pub fn active(&self) -> &[Status] {
+        &self.active
+    }
+
+    /// Every application, tick and expiry so far, oldest first.
+    pub fn log(&self) -> &[String] {
+        &self.log
+    }
+
+    /// The active effect of the given kind, if any.
+    pub fn get(&self, kind: EffectKind) -> Option<&Status> {
+        self.active.iter().find(|status| status.effect.kind() == kind)
+    }
+
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.get(kind).is_some()
+    }
+
+    fn record(&mut self, entry: String) -> String {
+        self.log.push(entry.clone());
+        entry
+    }
+}
+
+impl Character {
+    /// Puts an effect on the character for `turns` turns, following the
+    /// effect's stacking rule. Returns what happened.
+    ///
+    /// An effect lasting no turns at all is ignored.
+    pub fn apply_effect(&mut self, effect: Effect, turns: u32) -> String {
+        let name = &self.name;
+        let kind = effect.name();
+        let effects = &mut self.effects;
+        if turns == 0 {
+            return effects.record(format!("{}'s {} wears off at once.", name, kind));
+        }
+        let entry = match effects.active.iter_mut().find(|status| status.effect.kind() == effect.kind()) {
+            None => {
+                effects.active.push(Status { effect, turns });
+                match effect {
+                    Effect::Stun => format!("{} is stunned for {}.", name, plural_turns(turns)),
+                    _ => format!("{} gains {} {} for {}.", name, kind, effect.strength(), plural_turns(turns)),
+                }
+            }
+            Some(_) if effect.stacking() == Stacking::Ignore => format!("{} already has {}.", name, kind),
+            Some(status) => {
+                let strength = match effect.stacking() {
+                    Stacking::Intensify => status.effect.strength() + effect.strength(),
+                    _ => status.effect.strength().max(effect.strength()),
+                };
+                status.effect = status.effect.with_strength(strength);
+                status.turns = status.turns.max(turns);
+                format!("{}'s {} is now {} for {}.", name, kind, strength, plural_turns(status.turns))
+            }
+        };
+        effects.record(entry)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.effects.has(EffectKind::Stun)
+    }
+
+    /// Ticks every effect at the start of the character's turn: poison hurts,
+    /// regeneration heals, and each effect has one turn less to run. Returns
+    /// what happened.
+    pub fn start_turn(&mut self) -> Vec<String> {
+        let mut entries = vec![];
+        for status in &mut self.effects.active {
+            match status.effect {
+                Effect::Poison { damage } if self.health > 0 => {
+                    let dealt = damage.min(self.health);
+                    self.health -= dealt;
+                    let mut entry = format!("{} takes {} poison damage ({} HP left).", self.name, dealt, self.health);
+                    if self.health == 0 {
+                        entry.push_str(&format!(" {} is defeated!", self.name));
+                    }
+                    entries.push(entry);
+                }
+                Effect::Regeneration { heal } if self.health > 0 => {
+                    let healed = heal.min(self.max_health - self.health);
+                    self.health += healed;
+                    entries.push(format!("{} regenerates {} health.", self.name, healed));
+                }
+                _ => {}
+            }
+            status.turns -= 1;
+            if status.turns == 0 {
+                entries.push(format!("{}'s {} wears off.", self.name, status.effect.name()));
+            }
+        }
+        self.effects.active.retain(|status| status.turns > 0);
+        for entry in &entries {
+            self.effects.record(entry.clone());
+        }
+        entries
+    }
+
+    /// Lets an active shield take as much of `damage` as it can. Returns the
+    /// damage that gets through and how much the shield absorbed.
+    pub fn absorb(&mut self, damage: Health) -> (Health, Health) {
+        let shield = self
+            .effects
+            .active
+            .iter()
+            .position(|status| status.effect.kind() == EffectKind::Shield);
+        let Some(index) = shield else {
+            return (damage, 0);
+        };
+        let status = &mut self.effects.active[index];
+        let absorbed = damage.min(status.effect.strength());
+        let left = status.effect.strength() - absorbed;
+        status.effect = status.effect.with_strength(left);
+        if left == 0 {
+            self.effects.active.remove(index);
+            self.effects.record(format!("{}'s shield breaks.", self.name));
+        }
+        (damage - absorbed, absorbed)
+    }
+}
+
+fn plural_turns(turns: u32) -> String {
+    match turns {
+        1 => "1 turn".to_string(),
+        _ => format!("{} turns", turns),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concepts::compound_types::CharacterClass;
+
+    fn orc() -> Character {
+        Character::new("Orc".to_string(), CharacterClass::Warrior, 1)
+    }
+
+    #[test]
+    fn test_poison_ticks_and_wears_off() {
+        let mut orc = orc();
+        assert_eq!(orc.apply_effect(Effect::Poison { damage: 6 }, 2), "Orc gains poison 6 for 2 turns.");
+        assert_eq!(orc.start_turn(), ["Orc takes 6 poison damage (94 HP left)."]);
+        assert_eq!(
+            orc.start_turn(),
+            ["Orc takes 6 poison damage (88 HP left).", "Orc's poison wears off."]
+        );
+        assert!(orc.start_turn().is_empty());
+        assert_eq!(orc.health, 88);
+        assert_eq!(orc.effects.log().len(), 4);
+    }
+
+    #[test]
+    fn test_stacking_rules() {
+        let mut orc = orc();
+        orc.apply_effect(Effect::Poison { damage: 6 }, 3);
+        assert_eq!(orc.apply_effect(Effect::Poison { damage: 4 }, 2), "Orc's poison is now 10 for 3 turns.");
+
+        orc.apply_effect(Effect::Regeneration { heal: 8 }, 2);
+        assert_eq!(
+            orc.apply_effect(Effect::Regeneration { heal: 5 }, 4),
+            "Orc's regeneration is now 8 for 4 turns."
+        );
+
+        assert_eq!(orc.apply_effect(Effect::Stun, 1), "Orc is stunned for 1 turn.");
+        assert_eq!(orc.apply_effect(Effect::Stun, 3), "Orc already has stun.");
+        assert_eq!(orc.effects.get(EffectKind::Stun).unwrap().turns, 1);
+
+        // A stun only costs one turn, while the poison outweighs the healing
+        assert!(orc.is_stunned());
+        orc.start_turn();
+        assert!(!orc.is_stunned());
+        assert_eq!(orc.health, 98);
+    }
+
+    #[test]
+    fn test_shield_absorbs_until_broken() {
+        let mut orc = orc();
+        orc.apply_effect(Effect::Shield { absorb: 20 }, 3);
+        orc.apply_effect(Effect::Shield { absorb: 10 }, 1);
+        assert_eq!(orc.absorb(12), (0, 12));
+        assert_eq!(orc.absorb(25), (7, 18));
+        assert!(!orc.effects.has(EffectKind::Shield));
+        assert_eq!(orc.absorb(25), (25, 0));
+        assert_eq!(orc.effects.log().last().unwrap(), "Orc's shield breaks.");
+    }
+
+    #[test]
+    fn test_effect_lasting_no_turns_is_ignored() {
+        let mut orc = orc();
+        assert_eq!(orc.apply_effect(Effect::Poison { damage: 6 }, 0), "Orc's poison wears off at once.");
+        assert!(orc.effects.active().is_empty());
+        assert!(orc.start_turn().is_empty());
+        assert_eq!(orc.health, 100);
+    }
+}
@@ -0,0 +1,605 @@
+//! # Encounters
+//!
+//! An [`Encounter`] puts several [`Character`]s on two sides of a fight and
+//! resolves their [`Action`]s against each other: attacks hit the character
+//! their `target` id names, boosted by the attacker's weapon and softened by
+//! the target's armor, heals, spells and items charge the actor, and a dodge
+//! turns aside the next attack. Spells cast at another character put their
+//! status effect on that character, and effects tick at the start of each
+//! turn. Rounds run in initiative order until one side has nobody left
+//! standing, and the survivors share the experience for the foes they
+//! defeated.
+//!
+//! An encounter can also take place on a [`World`] map, where attackers need
+//! a clear line of sight to their target.
+
+use std::fmt;
+
+use super::progression::XP_PER_DEFEATED_LEVEL;
+use super::effects::EffectKind;
+use super::world::World;
+use super::{effects, Action, Character, CharacterClass, CharacterId, Health, Mana};
+
+/// Which side of the fight a character is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Party,
+    Foes,
+}
+
+impl Side {
+    pub fn opponent(self) -> Side {
+        match self {
+            Side::Party => Side::Foes,
+            Side::Foes => Side::Party,
+        }
+    }
+}
+
+/// How an encounter stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ongoing,
+    Victory(Side),
+    /// Both sides fell, e.g. the last attacker collapsed from exhaustion.
+    Draw,
+}
+
+/// Why an action could not be performed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CombatError {
+    UnknownCharacter(CharacterId),
+    /// The character has no health left.
+    Defeated(CharacterId),
+    TargetsSelf(CharacterId),
+    /// A wall stands between the attacker and the target.
+    OutOfSight { actor: CharacterId, target: CharacterId },
+    EncounterOver,
+}
+
+impl fmt::Display for CombatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CombatError::UnknownCharacter(id) => write!(f, "no character with id {}", id),
+            CombatError::Defeated(id) => write!(f, "character {} has been defeated", id),
+            CombatError::TargetsSelf(id) => write!(f, "character {} cannot attack themselves", id),
+            CombatError::OutOfSight { actor, target } => {
+                write!(f, "character {} cannot see character {}", actor, target)
+            }
+            CombatError::EncounterOver => write!(f, "the encounter is over"),
+        }
+    }
+}
+
+/// A character taking part in an encounter.
+#[derive(Debug, Clone)]
+pub struct Combatant {
+    pub character: Character,
+    pub side: Side,
+    /// Higher initiative acts earlier in each round.
+    pub initiative: u32,
+    /// Set by `Dodge`; cleared by the next attack or the character's next turn.
+    pub dodging: bool,
+}
+
+/// A fight between the party and its foes.
+#[derive(Debug, Clone, Default)]
+pub struct Encounter {
+    combatants: Vec<Combatant>,
+    round: u32,
+    /// Set once the winners have been given their experience.
+    rewarded: bool,
+    /// The map the fight takes place on, if any.
+    world: Option<World>,
+}
+
+impl Encounter {
+    pub fn new() -> Self {
+        Encounter::default()
+    }
+
+    /// An encounter on a map, where characters fight from where they stand.
+    pub fn on(world: World) -> Self {
+        Encounter {
+            world: Some(world),
+            ..Encounter::default()
+        }
+    }
+
+    pub fn world(&self) -> Option<&World> {
+        self.world.as_ref()
+    }
+
+    /// Whether `actor` has a clear line of sight to `target`. Always true off
+    /// the map.
+    pub fn in_sight(&self, actor: CharacterId, target: CharacterId) -> bool {
+        match (&self.world, self.character(actor), self.character(target)) {
+            (Some(world), Some(actor), Some(target)) => world.line_of_sight(actor.position, target.position),
+            _ => true,
+        }
+    }
+
+    /// Adds a character to one side, returning the id actions can target it by.
+    pub fn join(&mut self, character: Character, side: Side) -> CharacterId {
+        let initiative = initiative(&character);
+        self.combatants.push(Combatant {
+            character,
+            side,
+            initiative,
+            dodging: false,
+        });
+        self.combatants.len() - 1
+    }
+
+    pub fn combatant(&self, id: CharacterId) -> Option<&Combatant> {
+        self.combatants.get(id)
+    }
+
+    pub fn character(&self, id: CharacterId) -> Option<&Character> {
+        self.combatant(id).map(|combatant| &combatant.character)
+    }
+
+    /// Every combatant with its id, in the order they joined.
+    pub fn combatants(&self) -> impl Iterator<Item = (CharacterId, &Combatant)> {
+        self.combatants.iter().enumerate()
+    }
+
+    /// The number of rounds started so far.
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Living characters by descending initiative; ties go to whoever joined first.
+    pub fn turn_order(&self) -> Vec<CharacterId> {
+        let mut order: Vec<CharacterId> = self
+            .combatants()
+            .filter(|(_, combatant)| combatant.character.is_alive())
+            .map(|(id, _)| id)
+            .collect();
+        order.sort_by_key(|&id| std::cmp::Reverse(self.combatants[id].initiative));
+        order
+    }
+
+    /// Whether either side has won.
+    pub fn outcome(&self) -> Outcome {
+        let standing = |side| {
+            self.combatants
+                .iter()
+                .any(|combatant| combatant.side == side && combatant.character.is_alive())
+        };
+        match (standing(Side::Party), standing(Side::Foes)) {
+            (true, true) => Outcome::Ongoing,
+            (true, false) => Outcome::Victory(Side::Party),
+            (false, true) => Outcome::Victory(Side::Foes),
+            (false, false) => Outcome::Draw,
+        }
+    }
+
+    /// Resolves one action by `actor`, returning what happened.
+    pub fn perform(&mut self, actor: CharacterId, action: Action) -> Result<String, CombatError> {
+        if self.outcome() != Outcome::Ongoing {
+            return Err(CombatError::EncounterOver);
+        }
+        self.living(actor)?;
+
+        let action = match action {
+            Action::CastSpell { spell_name, mana_cost, target: Some(target) } if target == actor => {
+                Action::CastSpell { spell_name, mana_cost, target: None }
+            }
+            Action::CastSpell { spell_name, mana_cost, target: Some(target) } => {
+                return self.cast_at(actor, spell_name, mana_cost, target);
+            }
+            action => action,
+        };
+        let Action::Attack { damage, target } = action else {
+            let dodge = action == Action::Dodge;
+            let combatant = &mut self.combatants[actor];
+            combatant.dodging |= dodge;
+            return Ok(combatant.character.perform_action(action));
+        };
+
+        if target == actor {
+            return Err(CombatError::TargetsSelf(actor));
+        }
+        self.living(target)?;
+        if !self.in_sight(actor, target) {
+            return Err(CombatError::OutOfSight { actor, target });
+        }
+
+        // The attacker pays the stamina cost, then the target takes the hit
+        let target_name = self.combatants[target].character.name.clone();
+        let attacker = &mut self.combatants[actor].character;
+        let damage = attacker.attack_damage(damage);
+        attacker.perform_action(action);
+        let exhausted = (!attacker.is_alive()).then(|| format!(" {} collapses from exhaustion!", attacker.name));
+        let mut report = format!("{} attacks {}", attacker.name, target_name);
+
+        let defender = &mut self.combatants[target];
+        if defender.dodging {
+            defender.dodging = false;
+            report.push_str(&format!(", but {} dodges!", defender.character.name));
+        } else {
+            let (damage, absorbed) = defender.character.absorb(defender.character.damage_taken(damage));
+            let dealt = damage.min(defender.character.health);
+            defender.character.health -= dealt;
+            report.push_str(&format!(" for {} damage ({} HP left).", dealt, defender.character.health));
+            if absorbed > 0 {
+                report.push_str(&format!(" {}'s shield absorbs {}.", defender.character.name, absorbed));
+            }
+            if !defender.character.is_alive() {
+                report.push_str(&format!(" {} is defeated!", defender.character.name));
+            }
+        }
+        report.push_str(&exhausted.unwrap_or_default());
+        Ok(report)
+    }
+
+    /// Casts a spell at another character, who gets the spell's effect if it
+    /// has one.
+    fn cast_at(
+        &mut self,
+        actor: CharacterId,
+        spell_name: String,
+        mana_cost: Mana,
+        target: CharacterId,
+    ) -> Result<String, CombatError> {
+        self.living(target)?;
+        if !self.in_sight(actor, target) {
+            return Err(CombatError::OutOfSight { actor, target });
+        }
+
+        let target_name = self.combatants[target].character.name.clone();
+        let caster = &mut self.combatants[actor].character;
+        let affordable = caster.mana >= mana_cost;
+        let action = Action::CastSpell { spell_name: spell_name.clone(), mana_cost, target: Some(target) };
+        let result = caster.perform_action(action);
+        if !affordable {
+            return Ok(result);
+        }
+
+        let mut report = format!("{} casts {} at {}!", caster.name, spell_name, target_name);
+        if let Some(spell) = effects::spell(&spell_name) {
+            let effect = self.combatants[target].character.apply_effect(spell.effect, spell.turns);
+            report.push_str(&format!(" {}", effect));
+        }
+        Ok(report)
+    }
+
+    /// Runs one round: every living character acts once, in initiative order,
+    /// with `tactic` choosing their action. Each turn starts by ticking the
+    /// character's status effects, and a stunned character loses the turn.
+    /// Returns what happened.
+    pub fn run_round(&mut self, tactic: &mut impl FnMut(&Encounter, CharacterId) -> Action) -> Vec<String> {
+        self.round += 1;
+        let mut log = vec![];
+        for actor in self.turn_order() {
+            if self.outcome() != Outcome::Ongoing {
+                break;
+            }
+            if !self.combatants[actor].character.is_alive() {
+                continue;
+            }
+            // A dodge only lasts until the dodger's next turn
+            self.combatants[actor].dodging = false;
+            let character = &mut self.combatants[actor].character;
+            let stunned = character.is_stunned();
+            log.extend(character.start_turn());
+            if !character.is_alive() {
+                continue;
+            }
+            if stunned {
+                log.push(format!("{} is stunned and loses the turn!", character.name));
+                continue;
+            }
+            let action = tactic(self, actor);
+            match self.perform(actor, action) {
+                Ok(report) => log.push(report),
+                Err(err) => log.push(format!("{} hesitates: {}", self.combatants[actor].character.name, err)),
+            }
+        }
+        log
+    }
+
+    /// Runs rounds until one side wins or `max_rounds` have been fought, then
+    /// rewards the winners.
+    pub fn run(
+        &mut self,
+        max_rounds: u32,
+        tactic: &mut impl FnMut(&Encounter, CharacterId) -> Action,
+    ) -> (Outcome, Vec<String>) {
+        let mut log = vec![];
+        while self.outcome() == Outcome::Ongoing && self.round < max_rounds {
+            log.push(format!("--- Round {} ---", self.round + 1));
+            log.extend(self.run_round(tactic));
+        }
+        log.extend(self.reward());
+        (self.outcome(), log)
+    }
+
+    /// Once a side has won, splits the experience for every defeated opponent
+    /// evenly between its survivors. Does nothing before that, or a second time.
+    pub fn reward(&mut self) -> Vec<String> {
+        let Outcome::Victory(winners) = self.outcome() else {
+            return vec![];
+        };
+        if std::mem::replace(&mut self.rewarded, true) {
+            return vec![];
+        }
+
+        let total: u32 = self
+            .combatants
+            .iter()
+            .filter(|combatant| combatant.side != winners)
+            .map(|combatant| XP_PER_DEFEATED_LEVEL * combatant.character.level)
+            .sum();
+        let mut survivors: Vec<&mut Character> = self
+            .combatants
+            .iter_mut()
+            .filter(|combatant| combatant.side == winners && combatant.character.is_alive())
+            .map(|combatant| &mut combatant.character)
+            .collect();
+        let share = total / survivors.len() as u32;
+
+        let mut log = vec![];
+        for character in &mut survivors {
+            log.push(format!("{} gains {} XP.", character.name, share));
+            if character.gain_xp(share) > 0 {
+                log.push(format!("{} reaches level {}!", character.name, character.level));
+            }
+        }
+        log
+    }
+
+    fn living(&self, id: CharacterId) -> Result<&Combatant, CombatError> {
+        let combatant = self.combatant(id).ok_or(CombatError::UnknownCharacter(id))?;
+        if !combatant.character.is_alive() {
+            return Err(CombatError::Defeated(id));
+        }
+        Ok(combatant)
+    }
+}
+
+/// Rogues act first and mages last; higher levels act earlier within a class.
+fn initiative(character: &Character) -> u32 {
+    let base = match character.class {
+        CharacterClass::Rogue => 12,
+        CharacterClass::Warrior => 8,
+        CharacterClass::Mage => 6,
+    };
+    base + character.level
+}
+
+/// What the basic tactic's mages pay for Venom.
+const VENOM_COST: Mana = 15;
+
+/// A simple tactic: anyone below a third of their health drinks a potion if
+/// they have one and mages heal when badly hurt. Otherwise everyone goes for
+/// the weakest foe still standing that they can see: mages poison them with
+/// Venom while they have the mana, and everyone else attacks.
+pub fn basic_tactic(encounter: &Encounter, actor: CharacterId) -> Action {
+    let me = &encounter.combatants[actor];
+    if me.character.health * 3 < me.character.max_health {
+        if let Some(potion) = me.character.inventory.potion() {
+            return Action::UseItem { item: potion.name.clone() };
+        }
+    }
+    if me.character.class == CharacterClass::Mage && me.character.health < 25 {
+        return Action::Heal { amount: 20 };
+    }
+
+    let weakest = encounter
+        .combatants()
+        .filter(|(_, other)| other.side == me.side.opponent() && other.character.is_alive())
+        .filter(|&(id, _)| encounter.in_sight(actor, id))
+        .min_by_key(|(_, other)| other.character.health)
+        .map(|(id, _)| id);
+    match weakest {
+        Some(target)
+            if me.character.class == CharacterClass::Mage
+                && me.character.mana >= VENOM_COST
+                && !encounter.combatants[target].character.effects.has(EffectKind::Poison) =>
+        {
+            Action::CastSpell {
+                spell_name: "Venom".to_string(),
+                mana_cost: VENOM_COST,
+                target: Some(target),
+            }
+        }
+        Some(target) => Action::Attack {
+            damage: base_damage(&me.character),
+            target,
+        },
+        None => Action::Dodge,
+    }
+}
+
+fn base_damage(character: &Character) -> Health {
+    let base = match character.class {
+        CharacterClass::Warrior => 18,
+        CharacterClass::Rogue => 14,
+        CharacterClass::Mage => 10,
+    };
+    base + character.level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concepts::compound_types::inventory::Item;
+    use crate::concepts::compound_types::Position;
+
+    fn encounter() -> (Encounter, CharacterId, CharacterId) {
+        let mut encounter = Encounter::new();
+        let hero = encounter.join(Character::new("Aragorn".to_string(), CharacterClass::Warrior, 5), Side::Party);
+        let orc = encounter.join(Character::new("Orc".to_string(), CharacterClass::Rogue, 3), Side::Foes);
+        (encounter, hero, orc)
+    }
+
+    #[test]
+    fn test_attack_damages_the_target() {
+        let (mut encounter, hero, orc) = encounter();
+        let report = encounter.perform(hero, Action::Attack { damage: 30, target: orc }).unwrap();
+        assert_eq!(report, "Aragorn attacks Orc for 30 damage (66 HP left).");
+        assert_eq!(encounter.character(orc).unwrap().health, 66);
+        // The attacker still pays the stamina cost
+        assert_eq!(encounter.character(hero).unwrap().health, 138);
+
+        assert_eq!(
+            encounter.perform(hero, Action::Attack { damage: 30, target: 7 }),
+            Err(CombatError::UnknownCharacter(7))
+        );
+        assert_eq!(
+            encounter.perform(hero, Action::Attack { damage: 30, target: hero }),
+            Err(CombatError::TargetsSelf(hero))
+        );
+    }
+
+    #[test]
+    fn test_dodge_turns_aside_the_next_attack() {
+        let (mut encounter, hero, orc) = encounter();
+        assert_eq!(encounter.perform(orc, Action::Dodge).unwrap(), "Orc dodges an attack!");
+        let report = encounter.perform(hero, Action::Attack { damage: 30, target: orc }).unwrap();
+        assert_eq!(report, "Aragorn attacks Orc, but Orc dodges!");
+        assert_eq!(encounter.character(orc).unwrap().health, 96);
+
+        encounter.perform(hero, Action::Attack { damage: 30, target: orc }).unwrap();
+        assert_eq!(encounter.character(orc).unwrap().health, 66);
+    }
+
+    #[test]
+    fn test_heal_and_spells_charge_the_actor() {
+        let (mut encounter, hero, orc) = encounter();
+        encounter.perform(orc, Action::Attack { damage: 40, target: hero }).unwrap();
+        encounter.perform(hero, Action::Heal { amount: 15 }).unwrap();
+        assert_eq!(encounter.character(hero).unwrap().health, 123);
+        // Healing stops at the character's own maximum
+        encounter.perform(hero, Action::Heal { amount: 100 }).unwrap();
+        assert_eq!(encounter.character(hero).unwrap().health, 148);
+
+        let spell = Action::CastSpell { spell_name: "Smoke".to_string(), mana_cost: 30, target: None };
+        assert_eq!(encounter.perform(orc, spell.clone()).unwrap(), "Orc casts Smoke!");
+        assert_eq!(encounter.perform(orc, spell).unwrap(), "Orc lacks mana to cast Smoke!");
+        assert_eq!(encounter.character(orc).unwrap().mana, 20);
+    }
+
+    #[test]
+    fn test_defeat_ends_the_encounter() {
+        let (mut encounter, hero, orc) = encounter();
+        let report = encounter.perform(hero, Action::Attack { damage: 200, target: orc }).unwrap();
+        assert!(report.ends_with("for 96 damage (0 HP left). Orc is defeated!"), "{}", report);
+        assert_eq!(encounter.outcome(), Outcome::Victory(Side::Party));
+        assert_eq!(encounter.perform(hero, Action::Dodge), Err(CombatError::EncounterOver));
+    }
+
+    #[test]
+    fn test_survivors_share_experience() {
+        let (mut encounter, hero, orc) = encounter();
+        let mage = encounter.join(Character::new("Gandalf".to_string(), CharacterClass::Mage, 1), Side::Party);
+        assert!(encounter.reward().is_empty());
+
+        encounter.perform(orc, Action::Attack { damage: 200, target: mage }).unwrap();
+        encounter.perform(hero, Action::Attack { damage: 200, target: orc }).unwrap();
+        // Orc level 3 is worth 90 XP, all for the one survivor
+        assert_eq!(encounter.reward(), ["Aragorn gains 90 XP."]);
+        assert!(encounter.reward().is_empty());
+        assert_eq!(encounter.character(hero).unwrap().xp, 90);
+        assert_eq!(encounter.character(mage).unwrap().xp, 0);
+    }
+
+    #[test]
+    fn test_equipment_changes_the_damage_dealt() {
+        let (mut encounter, hero, orc) = encounter();
+        let gear = [Item::weapon("Longsword", 6, 8), Item::armor("Chain Mail", 4, 20)];
+        for item in gear {
+            let name = item.name.clone();
+            encounter.combatants[hero].character.pick_up(item, 1).unwrap();
+            encounter.perform(hero, Action::UseItem { item: name }).unwrap();
+        }
+
+        let report = encounter.perform(hero, Action::Attack { damage: 30, target: orc }).unwrap();
+        assert_eq!(report, "Aragorn attacks Orc for 36 damage (60 HP left).");
+        let report = encounter.perform(orc, Action::Attack { damage: 30, target: hero }).unwrap();
+        assert_eq!(report, "Orc attacks Aragorn for 26 damage (112 HP left).");
+
+        // Badly hurt characters reach for a potion before attacking again
+        let hero_character = &mut encounter.combatants[hero].character;
+        hero_character.pick_up(Item::potion("Healing Potion", 40, 1), 1).unwrap();
+        hero_character.health = 40;
+        let potion = Action::UseItem { item: "Healing Potion".to_string() };
+        assert_eq!(basic_tactic(&encounter, hero), potion);
+        encounter.perform(hero, potion).unwrap();
+        assert!(matches!(basic_tactic(&encounter, hero), Action::Attack { .. }));
+    }
+
+    #[test]
+    fn test_spells_put_effects_on_their_target() {
+        let (mut encounter, hero, orc) = encounter();
+        let cast = |spell: &str, mana_cost, target| Action::CastSpell {
+            spell_name: spell.to_string(),
+            mana_cost,
+            target: Some(target),
+        };
+
+        let report = encounter.perform(orc, cast("Hold", 10, hero)).unwrap();
+        assert_eq!(report, "Orc casts Hold at Aragorn! Aragorn is stunned for 1 turn.");
+        let report = encounter.perform(orc, cast("Barrier", 10, orc)).unwrap();
+        assert_eq!(report, "Orc casts Barrier! Orc gains shield 20 for 3 turns.");
+        assert_eq!(encounter.character(orc).unwrap().mana, 30);
+
+        // The stunned hero loses the turn; the orc's shield takes the first 20 damage
+        let log = encounter.run_round(&mut |_: &Encounter, actor| Action::Attack {
+            damage: 30,
+            target: if actor == hero { orc } else { hero },
+        });
+        assert_eq!(
+            log,
+            [
+                "Orc attacks Aragorn for 30 damage (118 HP left).",
+                "Aragorn's stun wears off.",
+                "Aragorn is stunned and loses the turn!",
+            ]
+        );
+        let report = encounter.perform(hero, Action::Attack { damage: 30, target: orc }).unwrap();
+        assert_eq!(report, "Aragorn attacks Orc for 10 damage (76 HP left). Orc's shield absorbs 20.");
+        assert!(!encounter.character(orc).unwrap().effects.has(EffectKind::Shield));
+    }
+
+    #[test]
+    fn test_attacks_need_line_of_sight_on_a_map() {
+        let world = World::parse("#######\n#..#..#\n#.....#\n#######").unwrap();
+        let mut encounter = Encounter::on(world);
+        let mut hero = Character::new("Aragorn".to_string(), CharacterClass::Warrior, 5);
+        hero.position = Position(1, 1);
+        let mut orc = Character::new("Orc".to_string(), CharacterClass::Rogue, 3);
+        orc.position = Position(5, 1);
+        let (hero, orc) = (encounter.join(hero, Side::Party), encounter.join(orc, Side::Foes));
+
+        assert!(!encounter.in_sight(hero, orc));
+        let attack = Action::Attack { damage: 30, target: orc };
+        let err = encounter.perform(hero, attack.clone()).unwrap_err();
+        assert_eq!(err, CombatError::OutOfSight { actor: hero, target: orc });
+        assert_eq!(err.to_string(), "character 0 cannot see character 1");
+        // With nobody in sight the basic tactic keeps its guard up
+        assert_eq!(basic_tactic(&encounter, hero), Action::Dodge);
+
+        encounter.combatants[hero].character.position = Position(1, 2);
+        encounter.combatants[orc].character.position = Position(5, 2);
+        assert!(encounter.perform(hero, attack).is_ok());
+    }
+
+    #[test]
+    fn test_rounds_follow_initiative() {
+        let (mut encounter, hero, orc) = encounter();
+        let mage = encounter.join(Character::new("Gandalf".to_string(), CharacterClass::Mage, 7), Side::Party);
+        // Orc 12 + 3, then Aragorn 8 + 5 and Gandalf 6 + 7 in joining order
+        assert_eq!(encounter.turn_order(), [orc, hero, mage]);
+
+        let log = encounter.run_round(&mut basic_tactic);
+        assert!(log[0].starts_with("Orc attacks Gandalf"), "{:?}", log);
+        assert!(log[1].starts_with("Aragorn attacks Orc"), "{:?}", log);
+
+        let (outcome, _) = encounter.run(20, &mut basic_tactic);
+        assert_eq!(outcome, Outcome::Victory(Side::Party));
+        assert!(encounter.turn_order().iter().all(|&id| encounter.combatant(id).unwrap().side == Side::Party));
+    }
+}
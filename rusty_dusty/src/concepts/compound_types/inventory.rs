@@ -0,0 +1,356 @@
+//! # Items and Inventory
+//!
+//! Characters carry [`Item`]s in an [`Inventory`]. Potions are consumables:
+//! identical ones stack, and using one heals the character through
+//! [`Action::Heal`]. Weapons and armor are equipment: using one equips it,
+//! after which a weapon adds to the damage the character deals and armor takes
+//! away from the damage they receive. Everything carried, equipped or not,
+//! counts towards the weight limit of the character's class.
+
+use std::fmt;
+
+use super::{progression, Action, Character, Health};
+
+/// What an item does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Potion { heal: Health },
+    Weapon { damage: Health },
+    Armor { defense: Health },
+}
+
+/// Something a character can carry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub name: String,
+    pub weight: u32,
+    pub kind: ItemKind,
+}
+
+impl Item {
+    pub fn potion(name: &str, heal: Health, weight: u32) -> Item {
+        Item::new(name, weight, ItemKind::Potion { heal })
+    }
+
+    pub fn weapon(name: &str, damage: Health, weight: u32) -> Item {
+        Item::new(name, weight, ItemKind::Weapon { damage })
+    }
+
+    pub fn armor(name: &str, defense: Health, weight: u32) -> Item {
+        Item::new(name, weight, ItemKind::Armor { defense })
+    }
+
+    fn new(name: &str, weight: u32, kind: ItemKind) -> Item {
+        Item {
+            name: name.to_string(),
+            weight,
+            kind,
+        }
+    }
+
+    /// Only consumables share a stack; each piece of equipment is carried on its own.
+    pub fn is_stackable(&self) -> bool {
+        matches!(self.kind, ItemKind::Potion { .. })
+    }
+}
+
+/// Some number of the same item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack {
+    pub item: Item,
+    pub count: u32,
+}
+
+/// How many stacks fit in a pack. Each piece of equipment takes one to itself.
+pub const PACK_SLOTS: usize = 32;
+
+/// Why an item could not be picked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InventoryError {
+    /// Asked to pick up none of it.
+    NothingToPickUp,
+    /// Carrying it would take the total weight past the class's limit. A total
+    /// too big to count is reported as `u32::MAX`.
+    TooHeavy { weight: u32, limit: u32 },
+    /// It needs more stacks than the pack has room for.
+    PackFull { slots: usize },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InventoryError::NothingToPickUp => write!(f, "there is nothing to pick up"),
+            InventoryError::TooHeavy { weight, limit } => {
+                write!(f, "that would weigh {}, over the limit of {}", weight, limit)
+            }
+            InventoryError::PackFull { slots } => write!(f, "the pack only has room for {} stacks", slots),
+        }
+    }
+}
+
+/// The items a character carries, and what they have equipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    stacks: Vec<Stack>,
+    weapon: Option<Item>,
+    armor: Option<Item>,
+}
+
+impl Inventory {
+    /// The unequipped items, in the order they were first picked up.
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    pub fn weapon(&self) -> Option<&Item> {
+        self.weapon.as_ref()
+    }
+
+    pub fn armor(&self) -> Option<&Item> {
+        self.armor.as_ref()
+    }
+
+    /// How many items named `name` are carried, not counting equipped ones.
+    pub fn count(&self, name: &str) -> u32 {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.item.name == name)
+            .map(|stack| stack.count)
+            .fold(0, u32::saturating_add)
+    }
+
+    /// The total weight of everything carried and equipped.
+    pub fn weight(&self) -> u32 {
+        let carried = self.stacks.iter().map(|stack| stack.item.weight.saturating_mul(stack.count));
+        let equipped = self.weapon.iter().chain(&self.armor).map(|item| item.weight);
+        carried.chain(equipped).fold(0, u32::saturating_add)
+    }
+
+    /// The first potion in the pack, if any.
+    pub fn potion(&self) -> Option<&Item> {
+        self.stacks
+            .iter()
+            .map(|stack| &stack.item)
+            .find(|item| matches!(item.kind, ItemKind::Potion { .. }))
+    }
+
+    /// Damage the equipped weapon adds to attacks.
+    pub fn attack_bonus(&self) -> Health {
+        match self.weapon.as_ref().map(|item| item.kind) {
+            Some(ItemKind::Weapon { damage }) => damage,
+            _ => 0,
+        }
+    }
+
+    /// Damage the equipped armor takes off each hit.
+    pub fn defense(&self) -> Health {
+        match self.armor.as_ref().map(|item| item.kind) {
+            Some(ItemKind::Armor { defense }) => defense,
+            _ => 0,
+        }
+    }
+
+    /// How many more stacks adding `count` of `item` would take up.
+    fn stacks_needed(&self, item: &Item, count: u32) -> usize {
+        if !item.is_stackable() {
+            count as usize
+        } else if self.stacks.iter().any(|stack| stack.item == *item) {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Adds items to the pack without checking the weight.
+    fn add(&mut self, item: Item, count: u32) {
+        if item.is_stackable() {
+            if let Some(stack) = self.stacks.iter_mut().find(|stack| stack.item == item) {
+                stack.count = stack.count.saturating_add(count);
+                return;
+            }
+            self.stacks.push(Stack { item, count });
+        } else {
+            self.stacks.extend((0..count).map(|_| Stack { item: item.clone(), count: 1 }));
+        }
+    }
+
+    /// Takes one item named `name` out of the pack.
+    fn take(&mut self, name: &str) -> Option<Item> {
+        let index = self.stacks.iter().position(|stack| stack.item.name == name)?;
+        let stack = &mut self.stacks[index];
+        stack.count -= 1;
+        let item = stack.item.clone();
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        Some(item)
+    }
+}
+
+impl Character {
+    /// The most weight the character's class can carry.
+    pub fn carry_limit(&self) -> u32 {
+        progression::stats(self.class).carry_weight
+    }
+
+    /// Puts `count` of an item in the character's pack, unless that would be
+    /// more than they can carry or more than the pack holds.
+    pub fn pick_up(&mut self, item: Item, count: u32) -> Result<String, InventoryError> {
+        if count == 0 {
+            return Err(InventoryError::NothingToPickUp);
+        }
+        let weight = item
+            .weight
+            .checked_mul(count)
+            .and_then(|weight| weight.checked_add(self.inventory.weight()));
+        match weight {
+            Some(weight) if weight <= self.carry_limit() => {}
+            _ => {
+                return Err(InventoryError::TooHeavy {
+                    weight: weight.unwrap_or(u32::MAX),
+                    limit: self.carry_limit(),
+                })
+            }
+        }
+        let stacks = self.inventory.stacks.len().saturating_add(self.inventory.stacks_needed(&item, count));
+        if stacks > PACK_SLOTS {
+            return Err(InventoryError::PackFull { slots: PACK_SLOTS });
+        }
+        let report = format!("{} picks up {} x {}", self.name, count, item.name);
+        self.inventory.add(item, count);
+        Ok(report)
+    }
+
+    /// The damage an attack does with the equipped weapon.
+    pub fn attack_damage(&self, base: Health) -> Health {
+        base + self.inventory.attack_bonus()
+    }
+
+    /// The damage a hit does after the equipped armor absorbs its share.
+    pub fn damage_taken(&self, incoming: Health) -> Health {
+        incoming.saturating_sub(self.inventory.defense())
+    }
+
+    /// Drinks a potion or equips a piece of equipment from the pack. Whatever
+    /// was equipped before goes back in the pack.
+    pub(super) fn use_item(&mut self, name: &str) -> String {
+        let Some(item) = self.inventory.take(name) else {
+            return format!("{} has no {}!", self.name, name);
+        };
+        let slot = match item.kind {
+            ItemKind::Potion { heal } => {
+                let healed = self.perform_action(Action::Heal { amount: heal });
+                return format!("{} drinks {}. {}", self.name, item.name, healed);
+            }
+            ItemKind::Weapon { .. } => &mut self.inventory.weapon,
+            ItemKind::Armor { .. } => &mut self.inventory.armor,
+        };
+        let report = format!("{} equips {}.", self.name, item.name);
+        if let Some(previous) = slot.replace(item) {
+            self.inventory.add(previous, 1);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concepts::compound_types::CharacterClass;
+
+    fn rogue() -> Character {
+        Character::new("Bilbo".to_string(), CharacterClass::Rogue, 1)
+    }
+
+    #[test]
+    fn test_potions_stack_and_heal() {
+        let mut bilbo = rogue();
+        bilbo.pick_up(Item::potion("Healing Potion", 25, 1), 2).unwrap();
+        bilbo.pick_up(Item::potion("Healing Potion", 25, 1), 1).unwrap();
+        assert_eq!(bilbo.inventory.stacks().len(), 1);
+        assert_eq!(bilbo.inventory.count("Healing Potion"), 3);
+
+        bilbo.health = 40;
+        let report = bilbo.perform_action(Action::UseItem { item: "Healing Potion".to_string() });
+        assert_eq!(report, "Bilbo drinks Healing Potion. Bilbo heals for 25 health!");
+        assert_eq!((bilbo.health, bilbo.inventory.count("Healing Potion")), (65, 2));
+
+        let report = bilbo.perform_action(Action::UseItem { item: "Elixir".to_string() });
+        assert_eq!(report, "Bilbo has no Elixir!");
+    }
+
+    #[test]
+    fn test_equipment_modifies_damage() {
+        let mut bilbo = rogue();
+        bilbo.pick_up(Item::weapon("Dagger", 4, 2), 1).unwrap();
+        bilbo.pick_up(Item::weapon("Sting", 8, 2), 1).unwrap();
+        bilbo.pick_up(Item::armor("Mithril Coat", 15, 5), 1).unwrap();
+        assert_eq!((bilbo.attack_damage(10), bilbo.damage_taken(20)), (10, 20));
+
+        assert_eq!(bilbo.perform_action(Action::UseItem { item: "Dagger".to_string() }), "Bilbo equips Dagger.");
+        bilbo.perform_action(Action::UseItem { item: "Mithril Coat".to_string() });
+        assert_eq!((bilbo.attack_damage(10), bilbo.damage_taken(20)), (14, 5));
+        assert_eq!(bilbo.damage_taken(10), 0);
+
+        // Swapping weapons puts the old one back in the pack
+        bilbo.perform_action(Action::UseItem { item: "Sting".to_string() });
+        assert_eq!(bilbo.inventory.weapon().map(|item| item.name.as_str()), Some("Sting"));
+        assert_eq!(bilbo.inventory.count("Dagger"), 1);
+        assert_eq!(bilbo.inventory.weight(), 9);
+    }
+
+    #[test]
+    fn test_weight_limit_depends_on_class() {
+        let mut bilbo = rogue();
+        let mut mage = Character::new("Gandalf".to_string(), CharacterClass::Mage, 1);
+        let plate = Item::armor("Plate Armor", 25, 30);
+
+        assert_eq!(
+            mage.pick_up(plate.clone(), 1),
+            Err(InventoryError::TooHeavy { weight: 30, limit: 25 })
+        );
+        assert!(bilbo.pick_up(plate, 1).is_ok());
+        let err = bilbo.pick_up(Item::potion("Healing Potion", 25, 1), 11).unwrap_err();
+        assert_eq!(err.to_string(), "that would weigh 41, over the limit of 40");
+        assert_eq!(bilbo.inventory.weight(), 30);
+    }
+
+    #[test]
+    fn test_picking_up_nothing_is_an_error() {
+        let mut bilbo = rogue();
+        let potion = Item::potion("Healing Potion", 25, 1);
+        assert_eq!(bilbo.pick_up(potion, 0), Err(InventoryError::NothingToPickUp));
+        assert!(bilbo.inventory.stacks().is_empty());
+    }
+
+    #[test]
+    fn test_overflowing_counts_are_too_heavy() {
+        let mut bilbo = rogue();
+        let limit = bilbo.carry_limit();
+        assert_eq!(
+            bilbo.pick_up(Item::potion("Healing Potion", 25, 2), u32::MAX),
+            Err(InventoryError::TooHeavy { weight: u32::MAX, limit })
+        );
+
+        // Weightless equipment still needs a slot per piece
+        assert_eq!(
+            bilbo.pick_up(Item::weapon("Feather", 1, 0), u32::MAX),
+            Err(InventoryError::PackFull { slots: PACK_SLOTS })
+        );
+        assert!(bilbo.pick_up(Item::weapon("Feather", 1, 0), PACK_SLOTS as u32).is_ok());
+        assert_eq!(bilbo.inventory.weight(), 0);
+    }
+
+    #[test]
+    fn test_taking_the_last_item_empties_the_stack() {
+        let mut bilbo = rogue();
+        bilbo.pick_up(Item::potion("Healing Potion", 25, 1), 2).unwrap();
+        for _ in 0..2 {
+            bilbo.perform_action(Action::UseItem { item: "Healing Potion".to_string() });
+        }
+        assert_eq!(bilbo.inventory.count("Healing Potion"), 0);
+        assert!(bilbo.inventory.stacks().is_empty());
+        let report = bilbo.perform_action(Action::UseItem { item: "Healing Potion".to_string() });
+        assert_eq!(report, "Bilbo has no Healing Potion!");
+    }
+}
@@ -0,0 +1,202 @@
+//! # Experience and Levels
+//!
+//! Characters earn experience from encounters and level up when they have
+//! enough of it. Each class grows by its own amounts per level, read from the
+//! table in `classes.txt` rather than written out in code, along with how much
+//! the class can carry:
+//!
+//! ```text
+//! # class    health  mana  health/level  mana/level  carry
+//! Warrior    100     20    12            2           60
+//! ```
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use super::{Character, CharacterClass, Health, Mana};
+
+/// The class table compiled into the binary.
+const CLASS_TABLE: &str = include_str!("classes.txt");
+
+/// Experience granted for defeating a character, per level of that character.
+pub const XP_PER_DEFEATED_LEVEL: u32 = 30;
+
+/// A class's stats at level 1, how they grow, and its carrying capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassStats {
+    pub class: CharacterClass,
+    pub health: Health,
+    pub mana: Mana,
+    pub health_per_level: Health,
+    pub mana_per_level: Mana,
+    /// The total weight of items the class can carry.
+    pub carry_weight: u32,
+}
+
+impl ClassStats {
+    pub fn max_health(&self, level: u32) -> Health {
+        self.health.saturating_add(self.health_per_level.saturating_mul(level.saturating_sub(1)))
+    }
+
+    pub fn max_mana(&self, level: u32) -> Mana {
+        self.mana.saturating_add(self.mana_per_level.saturating_mul(level.saturating_sub(1)))
+    }
+}
+
+/// A problem with a line of the class table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableError {
+    /// The 1-based line number.
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "class table line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Parses a class table: one whitespace-separated row per class, with `#`
+/// starting a comment.
+pub fn parse_table(text: &str) -> Result<Vec<ClassStats>, TableError> {
+    let mut table: Vec<ClassStats> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |reason: String| TableError { line: index + 1, reason };
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [class, numbers @ ..] = fields.as_slice() else {
+            unreachable!("a non-empty line has at least one field");
+        };
+        let class = match *class {
+            "Warrior" => CharacterClass::Warrior,
+            "Mage" => CharacterClass::Mage,
+            "Rogue" => CharacterClass::Rogue,
+            other => return Err(error(format!("unknown class `{}`", other))),
+        };
+        let numbers = numbers
+            .iter()
+            .map(|field| field.parse::<u32>().map_err(|_| error(format!("`{}` is not a whole number", field))))
+            .collect::<Result<Vec<u32>, _>>()?;
+        let [health, mana, health_per_level, mana_per_level, carry_weight] = numbers[..] else {
+            return Err(error(format!("expected 5 numbers, found {}", numbers.len())));
+        };
+        if table.iter().any(|stats| stats.class == class) {
+            return Err(error(format!("{:?} is listed twice", class)));
+        }
+        table.push(ClassStats {
+            class,
+            health,
+            mana,
+            health_per_level,
+            mana_per_level,
+            carry_weight,
+        });
+    }
+    Ok(table)
+}
+
+/// The stats of a class, from the built-in table.
+///
+/// # Panics
+///
+/// Panics if the built-in table is malformed or misses the class; the unit
+/// tests guard against both.
+pub fn stats(class: CharacterClass) -> &'static ClassStats {
+    static TABLE: OnceLock<Vec<ClassStats>> = OnceLock::new();
+    TABLE
+        .get_or_init(|| parse_table(CLASS_TABLE).unwrap_or_else(|err| panic!("{}", err)))
+        .iter()
+        .find(|stats| stats.class == class)
+        .unwrap_or_else(|| panic!("class table has no row for {:?}", class))
+}
+
+/// The experience needed to go from `level` to the next one.
+pub fn xp_to_next_level(level: u32) -> u32 {
+    level.saturating_mul(100)
+}
+
+impl Character {
+    /// Adds experience, levelling up as many times as it pays for. Each level
+    /// raises the health and mana caps by the class's growth, and the character
+    /// gains that much health and mana too. Returns the number of levels gained.
+    pub fn gain_xp(&mut self, xp: u32) -> u32 {
+        let stats = stats(self.class);
+        self.xp = self.xp.saturating_add(xp);
+        let mut gained = 0;
+        while self.xp >= xp_to_next_level(self.level) {
+            self.xp -= xp_to_next_level(self.level);
+            self.level += 1;
+            gained += 1;
+
+            self.max_health = stats.max_health(self.level);
+            self.max_mana = stats.max_mana(self.level);
+            self.health = self.health.saturating_add(stats.health_per_level).min(self.max_health);
+            self.mana = self.mana.saturating_add(stats.mana_per_level).min(self.max_mana);
+        }
+        gained
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concepts::compound_types::Action;
+
+    #[test]
+    fn test_builtin_table_covers_every_class() {
+        let table = parse_table(CLASS_TABLE).unwrap();
+        for class in [CharacterClass::Warrior, CharacterClass::Mage, CharacterClass::Rogue] {
+            assert!(table.iter().any(|stats| stats.class == class), "no row for {:?}", class);
+        }
+        assert_eq!(stats(CharacterClass::Mage).max_mana(3), 100);
+    }
+
+    #[test]
+    fn test_parse_table_errors() {
+        let err = parse_table("Warrior 1 2 3 4 5\n\nBard 1 2 3 4 5").unwrap_err();
+        assert_eq!(err.to_string(), "class table line 3: unknown class `Bard`");
+        assert_eq!(parse_table("Mage 1 2 3 4").unwrap_err().reason, "expected 5 numbers, found 4");
+        assert_eq!(parse_table("Mage 1 2 x 4 5").unwrap_err().reason, "`x` is not a whole number");
+        assert!(parse_table("Mage 1 2 3 4 5\nMage 1 2 3 4 5").is_err());
+    }
+
+    #[test]
+    fn test_gain_xp_levels_up_along_the_class_curve() {
+        let mut rogue = Character::new("Bilbo".to_string(), CharacterClass::Rogue, 1);
+        rogue.health = 50;
+        assert_eq!(rogue.gain_xp(90), 0);
+        // 100 to reach level 2, then 200 more for level 3
+        assert_eq!(rogue.gain_xp(220), 2);
+        assert_eq!((rogue.level, rogue.xp), (3, 10));
+        assert_eq!((rogue.max_health, rogue.max_mana), (96, 50));
+        assert_eq!((rogue.health, rogue.mana), (66, 50));
+    }
+
+    #[test]
+    fn test_huge_heals_stop_at_the_cap() {
+        let mut rogue = Character::new("Bilbo".to_string(), CharacterClass::Rogue, 1);
+        rogue.health = 10;
+        rogue.perform_action(Action::Heal { amount: Health::MAX });
+        assert_eq!(rogue.health, rogue.max_health);
+
+        rogue.health = Health::MAX;
+        rogue.mana = Mana::MAX;
+        rogue.gain_xp(100);
+        assert_eq!((rogue.health, rogue.mana), (rogue.max_health, rogue.max_mana));
+    }
+
+    #[test]
+    fn test_huge_xp_gains_do_not_overflow() {
+        let mut rogue = Character::new("Bilbo".to_string(), CharacterClass::Rogue, 1);
+        rogue.gain_xp(50);
+        assert!(rogue.gain_xp(u32::MAX) > 0);
+        assert!(rogue.xp < xp_to_next_level(rogue.level));
+        assert_eq!(rogue.max_health, stats(CharacterClass::Rogue).max_health(rogue.level));
+        assert_eq!(stats(CharacterClass::Rogue).max_health(u32::MAX), Health::MAX);
+    }
+}
@@ -0,0 +1,432 @@
+//! # The World Map
+//!
+//! A [`World`] is a grid of [`Tile`]s read from an ASCII map, one character per
+//! tile and one line per row:
+//!
+//! ```text
+//! #######
+//! #..~~.#
+//! #.""#.#
+//! #######
+//! ```
+//!
+//! `#` is a wall, `.` open floor, `"` brush and `~` shallow water. Walls stop
+//! movement and block line of sight; the other tiles can be crossed at a cost,
+//! and [`World::find_path`] finds the cheapest way between two [`Position`]s
+//! with A*. Positions are `(x, y)` with `(0, 0)` at the top left.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{Character, Position};
+
+/// One square of the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Floor,
+    Brush,
+    Water,
+    Wall,
+}
+
+impl Tile {
+    /// The tile a map character stands for.
+    pub fn from_char(ch: char) -> Option<Tile> {
+        match ch {
+            '.' => Some(Tile::Floor),
+            '"' => Some(Tile::Brush),
+            '~' => Some(Tile::Water),
+            '#' => Some(Tile::Wall),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Floor => '.',
+            Tile::Brush => '"',
+            Tile::Water => '~',
+            Tile::Wall => '#',
+        }
+    }
+
+    /// What it costs to step onto the tile, or `None` if it cannot be entered.
+    pub fn cost(self) -> Option<u32> {
+        match self {
+            Tile::Floor => Some(1),
+            Tile::Brush => Some(2),
+            Tile::Water => Some(3),
+            Tile::Wall => None,
+        }
+    }
+
+    pub fn blocks_sight(self) -> bool {
+        self == Tile::Wall
+    }
+}
+
+/// A problem with an ASCII map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    Empty,
+    UnknownTile { line: usize, column: usize, ch: char },
+    /// A row is not as wide as the first one.
+    Ragged { line: usize, width: usize, expected: usize },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Empty => write!(f, "the map has no rows"),
+            MapError::UnknownTile { line, column, ch } => {
+                write!(f, "map line {}, column {}: unknown tile `{}`", line, column, ch)
+            }
+            MapError::Ragged { line, width, expected } => {
+                write!(f, "map line {} is {} tiles wide, expected {}", line, width, expected)
+            }
+        }
+    }
+}
+
+/// Why a character cannot move somewhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    OutOfBounds(Position),
+    Blocked(Position),
+    /// The destination is open but walled off from where the character is.
+    Unreachable { from: Position, to: Position },
+    /// The character is not on open ground, e.g. because a new character
+    /// starts at `(0, 0)` and has not been placed on the map yet.
+    Stranded(Position),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::OutOfBounds(Position(x, y)) => write!(f, "({}, {}) is outside the map", x, y),
+            MoveError::Blocked(Position(x, y)) => write!(f, "({}, {}) is a wall", x, y),
+            MoveError::Unreachable { from, to } => write!(
+                f,
+                "there is no way from ({}, {}) to ({}, {})",
+                from.0, from.1, to.0, to.1
+            ),
+            MoveError::Stranded(Position(x, y)) => {
+                write!(f, "the character is at ({}, {}), which is not open ground", x, y)
+            }
+        }
+    }
+}
+
+/// A route found by [`World::find_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// Every position stepped onto, ending with the destination.
+    pub steps: Vec<Position>,
+    /// The summed cost of the tiles stepped onto.
+    pub cost: u32,
+}
+
+/// A rectangular tile map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    width: usize,
+    tiles: Vec<Tile>,
+}
+
+impl World {
+    /// Parses an ASCII map. Every row must be as wide as the first.
+    pub fn parse(text: &str) -> Result<World, MapError> {
+        let mut width = 0;
+        let mut tiles = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(column, ch)| {
+                    Tile::from_char(ch).ok_or(MapError::UnknownTile {
+                        line: index + 1,
+                        column: column + 1,
+                        ch,
+                    })
+                })
+                .collect::<Result<Vec<Tile>, _>>()?;
+            if index == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(MapError::Ragged {
+                    line: index + 1,
+                    width: row.len(),
+                    expected: width,
+                });
+            }
+            tiles.extend(row);
+        }
+        if tiles.is_empty() {
+            return Err(MapError::Empty);
+        }
+        Ok(World { width, tiles })
+    }
+
+    /// Reads and parses a map file. A malformed map is reported as
+    /// `InvalidData`.
+    pub fn load(path: &Path) -> io::Result<World> {
+        let text = fs::read_to_string(path)?;
+        World::parse(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.tiles.len() / self.width
+    }
+
+    /// The tile at `pos`, or `None` outside the map.
+    pub fn tile(&self, pos: Position) -> Option<Tile> {
+        let Position(x, y) = pos;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height() {
+            return None;
+        }
+        Some(self.tiles[y as usize * self.width + x as usize])
+    }
+
+    /// Checks that a character could stand at `pos`.
+    pub fn check_walkable(&self, pos: Position) -> Result<(), MoveError> {
+        match self.tile(pos) {
+            None => Err(MoveError::OutOfBounds(pos)),
+            Some(Tile::Wall) => Err(MoveError::Blocked(pos)),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// The cheapest route from `from` to `to`, moving one tile up, down, left
+    /// or right at a time. `None` if either end is not walkable or walls cut
+    /// them off from each other.
+    pub fn find_path(&self, from: Position, to: Position) -> Option<Route> {
+        self.check_walkable(from).ok()?;
+        self.check_walkable(to).ok()?;
+
+        // Every step costs at least 1, so the Manhattan distance never
+        // overestimates and the first time `to` is popped its route is cheapest
+        let estimate = |Position(x, y): Position| x.abs_diff(to.0) + y.abs_diff(to.1);
+        let mut cheapest: HashMap<Position, u32> = HashMap::from([(from, 0)]);
+        let mut came_from: HashMap<Position, Position> = HashMap::new();
+        // Ties go to the entry closer to the goal, then to the top-left
+        let mut open = BinaryHeap::from([Reverse((estimate(from), estimate(from), from.1, from.0))]);
+
+        while let Some(Reverse((_, _, y, x))) = open.pop() {
+            let current = Position(x, y);
+            let cost = cheapest[&current];
+            if current == to {
+                let mut steps = vec![current];
+                while let Some(&previous) = came_from.get(steps.last().unwrap()) {
+                    steps.push(previous);
+                }
+                steps.pop();
+                steps.reverse();
+                return Some(Route { steps, cost });
+            }
+
+            for next in neighbours(current) {
+                let Some(step) = self.tile(next).and_then(Tile::cost) else {
+                    continue;
+                };
+                let cost = cost + step;
+                if cheapest.get(&next).is_some_and(|&known| known <= cost) {
+                    continue;
+                }
+                cheapest.insert(next, cost);
+                came_from.insert(next, current);
+                open.push(Reverse((cost + estimate(next), estimate(next), next.1, next.0)));
+            }
+        }
+        None
+    }
+
+    /// Whether a straight line from `from` to `to` stays clear of anything that
+    /// blocks sight. The two ends themselves do not count.
+    pub fn line_of_sight(&self, from: Position, to: Position) -> bool {
+        if self.tile(from).is_none() || self.tile(to).is_none() {
+            return false;
+        }
+        line(from, to)
+            .into_iter()
+            .filter(|&pos| pos != from && pos != to)
+            .all(|pos| self.tile(pos).is_some_and(|tile| !tile.blocks_sight()))
+    }
+
+    /// Draws the map as ASCII, with each character shown by the first letter
+    /// of their name. Later characters are drawn over earlier ones.
+    pub fn render(&self, characters: &[&Character]) -> String {
+        let mut grid: Vec<Vec<char>> = self
+            .tiles
+            .chunks(self.width)
+            .map(|row| row.iter().map(|tile| tile.to_char()).collect())
+            .collect();
+        for character in characters {
+            let Position(x, y) = character.position;
+            if self.tile(character.position).is_some() {
+                grid[y as usize][x as usize] = character.name.chars().next().unwrap_or('@');
+            }
+        }
+        grid.into_iter()
+            .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+            .collect()
+    }
+}
+
+fn neighbours(Position(x, y): Position) -> [Position; 4] {
+    [Position(x, y - 1), Position(x + 1, y), Position(x, y + 1), Position(x - 1, y)]
+}
+
+/// The tiles on the line between two positions, ends included, after
+/// Bresenham's line algorithm.
+fn line(from: Position, to: Position) -> Vec<Position> {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let (mut x, mut y, mut error) = (from.0, from.1, dx + dy);
+    let mut points = vec![from];
+    while (x, y) != (to.0, to.1) {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        points.push(Position(x, y));
+    }
+    points
+}
+
+impl Character {
+    /// Walks the cheapest route to `pos`, if there is one.
+    ///
+    /// The character must already stand on open ground; set their `position`
+    /// to place them on the map first.
+    pub fn move_to(&mut self, world: &World, pos: Position) -> Result<String, MoveError> {
+        if world.check_walkable(self.position).is_err() {
+            return Err(MoveError::Stranded(self.position));
+        }
+        world.check_walkable(pos)?;
+        let route = world.find_path(self.position, pos).ok_or(MoveError::Unreachable {
+            from: self.position,
+            to: pos,
+        })?;
+        self.position = pos;
+        Ok(format!(
+            "{} moves to position ({}, {}) in {} steps (cost {})",
+            self.name,
+            pos.0,
+            pos.1,
+            route.steps.len(),
+            route.cost
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concepts::compound_types::CharacterClass;
+
+    const MAP: &str = "#######\n\
+                       #.~~~.#\n\
+                       #.###.#\n\
+                       #.....#\n\
+                       #######\n";
+
+    #[test]
+    fn test_parse_and_render_round_trip() {
+        let world = World::parse(MAP).unwrap();
+        assert_eq!((world.width(), world.height()), (7, 5));
+        assert_eq!(world.tile(Position(3, 1)), Some(Tile::Water));
+        assert_eq!(world.tile(Position(7, 1)), None);
+        assert_eq!(world.render(&[]), MAP);
+
+        let mut hero = Character::new("Aragorn".to_string(), CharacterClass::Warrior, 1);
+        hero.position = Position(1, 3);
+        assert!(world.render(&[&hero]).contains("#A....#"));
+
+        assert_eq!(World::parse(""), Err(MapError::Empty));
+        assert_eq!(
+            World::parse("###\n#x#").unwrap_err().to_string(),
+            "map line 2, column 2: unknown tile `x`"
+        );
+        assert_eq!(
+            World::parse("###\n##"),
+            Err(MapError::Ragged { line: 2, width: 2, expected: 3 })
+        );
+    }
+
+    #[test]
+    fn test_find_path_takes_the_cheapest_route() {
+        let world = World::parse(MAP).unwrap();
+        // Wading straight across costs 3 + 3 + 3 + 1 = 10; around the wall is 8
+        let path = world.find_path(Position(1, 1), Position(5, 1)).unwrap();
+        assert_eq!((path.steps.len(), path.cost), (8, 8));
+        assert_eq!(path.steps.first(), Some(&Position(1, 2)));
+        assert_eq!(path.steps.last(), Some(&Position(5, 1)));
+        assert!(path.steps.iter().all(|&pos| world.tile(pos) == Some(Tile::Floor)));
+
+        let path = world.find_path(Position(1, 1), Position(1, 1)).unwrap();
+        assert_eq!((path.steps.len(), path.cost), (0, 0));
+        assert_eq!(world.find_path(Position(1, 1), Position(0, 0)), None);
+
+        let walled = World::parse("#####\n#.#.#\n#####").unwrap();
+        assert_eq!(walled.find_path(Position(1, 1), Position(3, 1)), None);
+    }
+
+    #[test]
+    fn test_move_to_validates_the_destination() {
+        let world = World::parse(MAP).unwrap();
+        let mut hero = Character::new("Aragorn".to_string(), CharacterClass::Warrior, 1);
+        hero.position = Position(1, 3);
+
+        assert_eq!(hero.move_to(&world, Position(9, 9)), Err(MoveError::OutOfBounds(Position(9, 9))));
+        assert_eq!(hero.move_to(&world, Position(2, 2)), Err(MoveError::Blocked(Position(2, 2))));
+        assert_eq!(
+            hero.move_to(&world, Position(5, 1)).unwrap(),
+            "Aragorn moves to position (5, 1) in 6 steps (cost 6)"
+        );
+        assert_eq!(hero.position, Position(5, 1));
+
+        let walled = World::parse("#####\n#.#.#\n#####").unwrap();
+        hero.position = Position(1, 1);
+        let err = hero.move_to(&walled, Position(3, 1)).unwrap_err();
+        assert_eq!(err.to_string(), "there is no way from (1, 1) to (3, 1)");
+        assert_eq!(hero.position, Position(1, 1));
+    }
+
+    #[test]
+    fn test_move_to_needs_a_placed_character() {
+        let world = World::parse(MAP).unwrap();
+        let mut hero = Character::new("Aragorn".to_string(), CharacterClass::Warrior, 1);
+
+        // New characters start at (0, 0), which is a wall here
+        let err = hero.move_to(&world, Position(1, 1)).unwrap_err();
+        assert_eq!(err, MoveError::Stranded(Position(0, 0)));
+        assert_eq!(err.to_string(), "the character is at (0, 0), which is not open ground");
+
+        hero.position = Position(1, 3);
+        assert!(hero.move_to(&world, Position(1, 1)).is_ok());
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        let world = World::parse(MAP).unwrap();
+        // Water does not block sight, walls do
+        assert!(world.line_of_sight(Position(1, 1), Position(5, 1)));
+        assert!(world.line_of_sight(Position(1, 1), Position(1, 3)));
+        assert!(!world.line_of_sight(Position(3, 1), Position(3, 3)));
+        assert!(!world.line_of_sight(Position(1, 1), Position(5, 3)));
+        assert!(!world.line_of_sight(Position(1, 1), Position(9, 1)));
+    }
+}
@@ -1,70 +1,77 @@
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
+use crate::demo::{Category, Entry, Mask};
+use crate::params::{Param, ParamKind};
+
 /// Demonstrates control flow concepts in Rust including:
 ///
 /// - `for`, `while`, and `loop` constructs with `break`, `enumerate`, and value returns
 /// - Conditional branching using `if`, `else if`, and `else`
 /// - Pattern matching using `match`, including guards, ranges, tuples, and `Option`
-/// - Time-based loop to measure how many iterations happen per second
-pub fn main() {
-    loops_demo();
-    if_else_demo();
-    match_demo();
-    increment_per_second();
+/// - Time-based loop to measure how many iterations happen per second,
+///   counting for `time_limit`
+pub fn main(time_limit: Duration, out: &mut dyn Write) -> io::Result<()> {
+    loops_demo(out)?;
+    if_else_demo(out)?;
+    match_demo(out)?;
+    increment_per_second(time_limit, out)?;
+
+    Ok(())
 }
 
-fn loops_demo() {
-    println!("Loops");
+fn loops_demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Loops")?;
 
     // for loop examples.
-    println!("For Loops:");
+    writeln!(out, "For Loops:")?;
 
     // basic range loop.
-    print!("Basic range: ");
+    write!(out, "Basic range: ")?;
     for num in 1..=5 {
-        print!("{num} ");
+        write!(out, "{num} ")?;
     }
-    println!();
+    writeln!(out)?;
 
     // iterate over array.
-    print!("Array iteration: ");
+    write!(out, "Array iteration: ")?;
     let numbers = [1, 2, 3, 4, 5];
     for num in numbers {
-        print!("{num} ");
+        write!(out, "{num} ")?;
     }
-    println!();
+    writeln!(out)?;
 
     // iterate with enumerate.
-    print!("Enumerate: ");
+    write!(out, "Enumerate: ")?;
     for (index, value) in numbers.iter().enumerate() {
-        print!("{}:{} ", index, value);
+        write!(out, "{}:{} ", index, value)?;
     }
-    println!();
+    writeln!(out)?;
 
     // while loop examples
-    println!("\n2. While Loops:");
+    writeln!(out, "\n2. While Loops:")?;
 
     let mut counter = 1;
     '_outer: while counter <= 5 {
-        print!("{counter} ");
+        write!(out, "{counter} ")?;
         counter += 1;
     }
-    println!();
+    writeln!(out)?;
 
     // loop (Infinite Loop) examples.
-    println!("\n3. Loop Examples:");
+    writeln!(out, "\n3. Loop Examples:")?;
 
     // basic loop with break.
-    print!("Basic loop with break: ");
+    write!(out, "Basic loop with break: ")?;
     let mut count = 0;
     loop {
-        print!("{count} ");
+        write!(out, "{count} ")?;
         count += 1;
         if count == 5 {
             break;
         }
     }
-    println!();
+    writeln!(out)?;
 
     // loop with return value.
     let result = loop {
@@ -73,76 +80,106 @@ fn loops_demo() {
         }
         count += 1;
     };
-    println!("Loop with return value: {result}\n");
+    writeln!(out, "Loop with return value: {result}\n")?;
+
+    Ok(())
 }
 
-fn if_else_demo() {
-    println!("If/Else");
+fn if_else_demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "If/Else")?;
 
     let age = 25;
     let has_license = true;
 
     if age >= 18 && has_license {
-        println!("Can drive");
+        writeln!(out, "Can drive")?;
     } else if age >= 18 {
-        println!("Need to get a license");
+        writeln!(out, "Need to get a license")?;
     } else {
-        println!("Too young to drive");
+        writeln!(out, "Too young to drive")?;
     }
 
     let arr = [1, 2, 4];
     if let Some(max) = arr.get(2) {
-        println!("Maximum is configured to be {max}");
+        writeln!(out, "Maximum is configured to be {max}")?;
     }
 
     let condition = true;
     let value = if condition { 5 } else { 6 };
-    println!("Value from if expression: {value}\n");
+    writeln!(out, "Value from if expression: {value}\n")?;
+
+    Ok(())
 }
 
-fn match_demo() {
-    println!("Expression");
+fn match_demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Expression")?;
 
     let number = 13;
     match number {
-        1 => println!("One"),
-        2 | 3 | 5 | 7 | 11 | 13 => println!("This is a prime number"),
-        _ => println!("Something else"),
+        1 => writeln!(out, "One")?,
+        2 | 3 | 5 | 7 | 11 | 13 => writeln!(out, "This is a prime number")?,
+        _ => writeln!(out, "Something else")?,
     }
 
     let grade = 85;
     match grade {
-        90..=100 => println!("A"),
-        80..=89 => println!("B"),
-        70..=79 => println!("C"),
-        60..=69 => println!("D"),
-        _ => println!("F"),
+        90..=100 => writeln!(out, "A")?,
+        80..=89 => writeln!(out, "B")?,
+        70..=79 => writeln!(out, "C")?,
+        60..=69 => writeln!(out, "D")?,
+        _ => writeln!(out, "F")?,
     }
 
     let pair = (2, -2);
     match pair {
-        (x, y) if x == y => println!("Equal"),
-        (x, y) if x + y == 0 => println!("Sum to zero"),
-        (x, _) if x % 2 == 0 => println!("First is even"),
-        _ => println!("No match"),
+        (x, y) if x == y => writeln!(out, "Equal")?,
+        (x, y) if x + y == 0 => writeln!(out, "Sum to zero")?,
+        (x, _) if x % 2 == 0 => writeln!(out, "First is even")?,
+        _ => writeln!(out, "No match")?,
     }
 
     let msg = Some(String::from("Hello"));
     match msg {
-        Some(text) if text.len() > 5 => println!("Long message: {text}"),
-        Some(text) => println!("Short message: {text}"),
-        None => println!("No message"),
+        Some(text) if text.len() > 5 => writeln!(out, "Long message: {text}")?,
+        Some(text) => writeln!(out, "Short message: {text}")?,
+        None => writeln!(out, "No message")?,
     }
-    println!();
+    writeln!(out)?;
+
+    Ok(())
 }
 
-fn increment_per_second() {
-    let mut count = 0;
-    let time_limit = Duration::new(1, 0);
+fn increment_per_second(time_limit: Duration, out: &mut dyn Write) -> io::Result<()> {
+    let mut count: u64 = 0;
     let start = Instant::now();
     
     while (Instant::now() - start) < time_limit {
         count += 1;
     }
-    println!("Counting speed per second: {count}");
+    let per_second = (count as f64 / time_limit.as_secs_f64()) as u64;
+    writeln!(out, "Counting speed per second: {per_second}")?;
+
+    Ok(())
 }
+
+/// Knobs for the counting loop.
+const PARAMS: &[Param] = &[Param {
+    name: "time-limit",
+    help: "How long the counting loop runs",
+    kind: ParamKind::Duration {
+        max: Duration::from_secs(60),
+    },
+    default: "1s",
+}];
+
+/// Registry entry for the `control_flow` demo.
+pub const DEMO: Entry = Entry {
+    name: "control_flow",
+    category: Category::Concepts,
+    summary: "Loops, `if` expressions and `match` with guards, ranges and tuples.",
+    prerequisites: &["variables"],
+    params: PARAMS,
+    masks: &[Mask::After("Counting speed per second: ")],
+    run: |params, out| main(params.duration("time-limit"), out),
+    ..Entry::DEFAULTS
+};
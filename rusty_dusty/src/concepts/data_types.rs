@@ -3,9 +3,13 @@
 //! This module demonstrates basic built-in data types in Rust,
 //! including arrays, tuples, floating-point numbers, and booleans.
 
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
+
 /// Entry point demonstrating usage of several basic Rust data types.
-pub fn main() {
-    println!("Data-Types");
+pub fn main(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Data-Types")?;
 
     // Array of integers
     let arr = [1, 2, 3, 4, 5];
@@ -16,8 +20,20 @@ pub fn main() {
     let float_val = 10.7;
     let is_alive = true;
 
-    println!("float = {}, bool = {}", float_val, is_alive);
-    println!("arr = {:#?}, tuple = {:?}", arr, tup);
+    writeln!(out, "float = {}, bool = {}", float_val, is_alive)?;
+    writeln!(out, "arr = {:#?}, tuple = {:?}", arr, tup)?;
 
-    println!();
+    writeln!(out)?;
+
+    Ok(())
 }
+
+/// Registry entry for the `data_types` demo.
+pub const DEMO: Entry = Entry {
+    name: "data_types",
+    category: Category::Concepts,
+    summary: "Built-in data types: arrays, tuples, floats and booleans.",
+    prerequisites: &["variables"],
+    run: |_, out| main(out),
+    ..Entry::DEFAULTS
+};
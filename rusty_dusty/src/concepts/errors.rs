@@ -1,35 +1,40 @@
 use std::{
     fmt,
     fs::File,
-    io::{self, ErrorKind, Read},
+    io::{self, ErrorKind, Read, Write},
 };
 
+use crate::demo::{Category, Entry};
+use crate::exercise::Exercise;
+
 /// Demonstrates various error handling mechanisms including recoverable and custom errors.
-pub fn main() {
-    recoverable();
+pub fn main(out: &mut dyn Write) -> io::Result<()> {
+    recoverable(out)?;
 
     match read_file_contents("files/text.txt") {
-        Ok(contents) => println!("File contents: {contents}"),
-        Err(err) => println!("Error: {err}"),
+        Ok(contents) => writeln!(out, "File contents: {contents}")?,
+        Err(err) => writeln!(out, "Error: {err}")?,
     }
 
     let result = divide(10, 2);
     match result {
-        Ok(result) => println!("Result: {result}\n"),
-        Err(err) => println!("Error: {err}"),
+        Ok(result) => writeln!(out, "Result: {result}\n")?,
+        Err(err) => writeln!(out, "Error: {err}")?,
     }
 
     match read_and_parse_file("files/numbers.txt") {
-        Ok(number) => println!("The number is: {}", number),
-        Err(e) => println!("An error occurred: {}", e),
+        Ok(number) => writeln!(out, "The number is: {}", number)?,
+        Err(e) => writeln!(out, "An error occurred: {}", e)?,
     }
+
+    Ok(())
 }
 
 /// Demonstrates handling a recoverable error when trying to open a file.
 /// If the file doesn't exist, it attempts to create it.
 /// Panics if an unexpected error occurs during opening or creating the file.
-fn recoverable() {
-    println!("Recoverable Error!");
+fn recoverable(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Recoverable Error!")?;
 
     let file_result = File::open("files/hello.txt");
 
@@ -46,7 +51,9 @@ fn recoverable() {
         },
     };
 
-    println!();
+    writeln!(out)?;
+
+    Ok(())
 }
 
 /// Attempts to divide two integers, returning a `Result`.
@@ -134,3 +141,21 @@ fn read_and_parse_file(path: &str) -> Result<i32, MyError> {
     let number: i32 = contents.trim().parse()?;
     Ok(number)
 }
+
+/// Exercises on this topic; the stubs live in `exercises/`.
+const EXERCISES: &[Exercise] = &[Exercise {
+    name: "errors_parse",
+    summary: "Parse input into `Result`s with a custom error type and `?`.",
+    tests: include_str!("../checks/errors_parse.rs"),
+}];
+
+/// Registry entry for the `errors` demo.
+pub const DEMO: Entry = Entry {
+    name: "errors",
+    category: Category::Concepts,
+    summary: "Recoverable errors with `Result`, the `?` operator and a custom error type.",
+    prerequisites: &["control_flow"],
+    exercises: EXERCISES,
+    run: |_, out| main(out),
+    ..Entry::DEFAULTS
+};
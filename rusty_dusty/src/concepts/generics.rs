@@ -1,4 +1,7 @@
 use std::fmt::{Debug, Display};
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
 
 /// Returns the largest element in a list of comparable items.
 ///
@@ -21,10 +24,12 @@ use std::fmt::{Debug, Display};
 /// # Example
 ///
 /// ```
+/// use rusty_dusty::concepts::generics::largest;
+///
 /// let numbers = vec![10, 40, 30];
 /// assert_eq!(largest(&numbers), 40);
 /// ```
-fn largest<T: PartialOrd + Copy>(items: &[T]) -> T {
+pub fn largest<T: PartialOrd + Copy>(items: &[T]) -> T {
     let mut max = items[0];
 
     for &item in items.iter() {
@@ -46,18 +51,20 @@ fn largest<T: PartialOrd + Copy>(items: &[T]) -> T {
 /// # Example
 ///
 /// ```
+/// use rusty_dusty::concepts::generics::Point;
+///
 /// let p = Point { x: 1, y: 3.5 };
 /// println!("{}", p.describe());
 /// ```
 #[derive(Debug)]
-struct Point<X: Debug, Y: Display> {
-    x: X,
-    y: Y,
+pub struct Point<X: Debug, Y: Display> {
+    pub x: X,
+    pub y: Y,
 }
 
 impl<X: Debug, Y: Display> Point<X, Y> {
     /// Describes the point by printing x and y.
-    fn describe(&self) -> String {
+    pub fn describe(&self) -> String {
         format!("Point at x = {:?}, y = {}", self.x, self.y)
     }
 }
@@ -71,8 +78,8 @@ struct Wrapper<T: Display> {
 }
 
 impl<T: Display> Wrapper<T> {
-    fn show(&self) {
-        println!("Wrapped value: {}", self.value);
+    fn show(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Wrapped value: {}", self.value)
     }
 }
 
@@ -82,25 +89,37 @@ impl<T: Display> Wrapper<T> {
 /// - Finds largest items in lists
 /// - Uses generic `Point` with different coordinate types
 /// - Wraps a `String` in a generic `Wrapper`
-pub fn demo() {
-    println!("=== Generics Demo ===");
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "=== Generics Demo ===")?;
 
     // Generic function usage
     let nums = vec![1, 5, 3, 9, 2];
     let max_num = largest(&nums);
-    println!("Largest number: {}", max_num);
+    writeln!(out, "Largest number: {}", max_num)?;
 
     let chars = vec!['g', 'z', 'a', 'b'];
     let max_char = largest(&chars);
-    println!("Largest char: {}", max_char);
+    writeln!(out, "Largest char: {}", max_char)?;
 
     // Generic struct usage
-    let point = Point { x: 10, y: 3.14 };
-    println!("{}", point.describe());
+    let point = Point { x: 10, y: 3.5 };
+    writeln!(out, "{}", point.describe())?;
 
     // Wrapper example
     let wrapped = Wrapper {
         value: String::from("Rust"),
     };
-    wrapped.show();
+    wrapped.show(out)?;
+
+    Ok(())
 }
+
+/// Registry entry for the `generics` demo.
+pub const DEMO: Entry = Entry {
+    name: "generics",
+    category: Category::Concepts,
+    summary: "Generic functions and structs with trait bounds.",
+    prerequisites: &["compound_types"],
+    run: |_, out| demo(out),
+    ..Entry::DEFAULTS
+};
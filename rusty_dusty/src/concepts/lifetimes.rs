@@ -1,38 +1,46 @@
-pub fn demo() {
-    println!("\n1. Basic Reference Example:");
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
+
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n1. Basic Reference Example:")?;
     {
         let original = String::from("hello");
         let reference = &original;
-        println!("Original: {}", original);
-        println!("Reference: {}", reference);
+        writeln!(out, "Original: {}", original)?;
+        writeln!(out, "Reference: {}", reference)?;
     } 
 
-    println!("\n2. Function with Lifetime:");
+    writeln!(out, "\n2. Function with Lifetime:")?;
     {
         let string1 = String::from("short string");
         let string2 = String::from("longer string");
         
         // The compiler knows these references must have the same lifetime.
         let result = longest(&string1, &string2);
-        println!("Longest string is: {}", result);
+        writeln!(out, "Longest string is: {}", result)?;
     }
 
-    println!("\n3. Struct with Lifetime:");
+    writeln!(out, "\n3. Struct with Lifetime:")?;
     {
         let novel = String::from("Call me Ishmael. Some years ago...");
         let first_sentence = get_first_sentence(&novel);
         let excerpt = ImportantExcerpt {
             part: first_sentence,
         };
-        println!("Excerpt: {}", excerpt.part);
-        println!("Importance level: {}", excerpt.level());
+        writeln!(out, "Excerpt: {}", excerpt.part)?;
+        writeln!(out, "Importance level: {}", excerpt.level())?;
+        let part = excerpt.announce_and_return_part("new excerpt", out)?;
+        writeln!(out, "Returned part: {}", part)?;
     }
 
-    println!("\n4. Static Lifetime Example:");
+    writeln!(out, "\n4. Static Lifetime Example:")?;
     {
         let static_str: &'static str = "I have a static lifetime";
-        print_static(static_str);
+        print_static(static_str, out)?;
     }
+
+    Ok(())
 }
 
 
@@ -51,14 +59,20 @@ impl<'a> ImportantExcerpt<'a> {
         3
     }
 
-    fn announce_and_return_part<'b>(&self, announcement: &'b str) -> &'a str {
-        println!("Attention please: {}", announcement);
-        self.part
+    fn announce_and_return_part<'b>(
+        &self,
+        announcement: &'b str,
+        out: &mut dyn Write,
+    ) -> io::Result<&'a str> {
+        writeln!(out, "Attention please: {}", announcement)?;
+        Ok(self.part)
     }
 }
 
-fn print_static(s: &'static str) {
-    println!("Static string: {}", s);
+fn print_static(s: &'static str, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Static string: {}", s)?;
+
+    Ok(())
 }
 
 // Helper function: Get first sentence of a string.
@@ -67,4 +81,14 @@ fn get_first_sentence(text: &str) -> &str {
         Some(idx) => &text[..=idx],
         None => text,
     }
-}
\ No newline at end of file
+}
+
+/// Registry entry for the `lifetimes` demo.
+pub const DEMO: Entry = Entry {
+    name: "lifetimes",
+    category: Category::Concepts,
+    summary: "Lifetime annotations on functions and structs, and `'static` references.",
+    prerequisites: &["own"],
+    run: |_, out| demo(out),
+    ..Entry::DEFAULTS
+};
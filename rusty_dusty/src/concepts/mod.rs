@@ -1,3 +1,5 @@
+use crate::demo::Demo;
+
 pub mod array;
 pub mod control_flow;
 pub mod data_types;
@@ -10,4 +12,23 @@ pub mod strings;
 pub mod compound_types;
 pub mod traits;
 pub mod tuples;
-pub mod variables;
\ No newline at end of file
+pub mod variables;
+
+/// The concept demos, in suggested learning order.
+pub fn demos() -> &'static [&'static dyn Demo] {
+    &[
+        &variables::DEMO,
+        &data_types::DEMO,
+        &array::DEMO,
+        &tuples::DEMO,
+        &strings::DEMO,
+        &control_flow::DEMO,
+        &errors::DEMO,
+        &option::DEMO,
+        &compound_types::DEMO,
+        &own::DEMO,
+        &generics::DEMO,
+        &traits::DEMO,
+        &lifetimes::DEMO,
+    ]
+}
@@ -1,36 +1,45 @@
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
+use crate::exercise::Exercise;
+
 /// Demonstrates usage of the `Option` enum and pattern matching
 /// to handle potentially missing values and compute results.
-pub fn main() {
-    println!("Option<Some, None>");
-    option();
+pub fn main(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Option<Some, None>")?;
+    option(out)?;
 
-    println!("\nChecking if numbers are even:");
+    writeln!(out, "\nChecking if numbers are even:")?;
     let numbers = [2, 3, 4, 7, -1];
 
     for &num in &numbers {
         match is_even(num) {
-            Some(true) => println!("{num} is even"),
-            Some(false) => println!("{num} is odd"),
-            None => println!("Unable to determine for {num}"),
+            Some(true) => writeln!(out, "{num} is even")?,
+            Some(false) => writeln!(out, "{num} is odd")?,
+            None => writeln!(out, "Unable to determine for {num}")?,
         }
     }
 
-    println!();
+    writeln!(out)?;
+
+    Ok(())
 }
 
 /// Demonstrates accessing the first element of an array using `Option`.
 ///
 /// If the array is empty, it will print `None`, otherwise it prints the first element.
-fn option() {
-    println!("Option Enum!");
+fn option(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Option Enum!")?;
 
     let arr = [1, 3, 4];
     let first = arr.first();
 
     match first {
-        Some(value) => println!("First Element: {value}"),
-        None => println!("List is Empty"),
+        Some(value) => writeln!(out, "First Element: {value}")?,
+        None => writeln!(out, "List is Empty")?,
     }
+
+    Ok(())
 }
 
 /// Determines if a number is even.
@@ -53,3 +62,21 @@ fn is_even(num: i32) -> Option<bool> {
         None
     }
 }
+
+/// Exercises on this topic; the stubs live in `exercises/`.
+const EXERCISES: &[Exercise] = &[Exercise {
+    name: "option_basics",
+    summary: "Find, divide and chain with `Option` instead of sentinel values.",
+    tests: include_str!("../checks/option_basics.rs"),
+}];
+
+/// Registry entry for the `option` demo.
+pub const DEMO: Entry = Entry {
+    name: "option",
+    category: Category::Concepts,
+    summary: "The `Option` enum and pattern matching on values that may be missing.",
+    prerequisites: &["control_flow"],
+    exercises: EXERCISES,
+    run: |_, out| main(out),
+    ..Entry::DEFAULTS
+};
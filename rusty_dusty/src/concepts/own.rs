@@ -1,61 +1,157 @@
-use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Write};
+use std::rc::{Rc, Weak};
+
+use crate::alloc_count::{self, Usage};
+use crate::demo::{Category, Entry};
+use crate::params::{Param, ParamKind};
+
+pub mod broadcast;
+pub mod durable;
+pub mod protocol;
+pub mod reliable;
+pub mod schedule;
+pub mod threaded;
+pub mod trace;
 
 /// Represents a CubeSat with a unique identifier.
 #[derive(Debug, Clone, Copy)]
-struct CubeSat {
-    id: u64,
+pub struct CubeSat {
+    pub id: u64,
 }
 
 /// A mailbox that stores messages for CubeSats.
-#[derive(Debug, Clone)]
-struct Mailbox {
-    messages: Vec<Message>,
+///
+/// Messages are queued per recipient and delivered oldest first. The queues are
+/// kept in a `BTreeMap` so the mailbox prints in a stable order.
+#[derive(Debug, Default)]
+pub struct Mailbox {
+    queues: BTreeMap<u64, VecDeque<Message>>,
 }
 
 /// A message addressed to a specific CubeSat.
-#[derive(Debug, Clone)]
-struct Message {
-    to: u64,
-    content: String,
+#[derive(Debug, PartialEq, Eq)]
+pub struct Message {
+    pub to: u64,
+    pub content: String,
+}
+
+// Cloning is what the ownership methods below try to avoid, so `Mailbox` and
+// `Message` report each clone to the allocation counter.
+impl Clone for Mailbox {
+    fn clone(&self) -> Self {
+        alloc_count::record_clone();
+        Mailbox {
+            queues: self.queues.clone(),
+        }
+    }
+}
+
+impl Clone for Message {
+    fn clone(&self) -> Self {
+        alloc_count::record_clone();
+        Message {
+            to: self.to,
+            content: self.content.clone(),
+        }
+    }
 }
 
 /// GroundStation is responsible for creating CubeSats and sending messages.
-struct GroundStation;
+pub struct GroundStation;
+
+/// A named ground station that shares one mailbox with other stations.
+///
+/// Every station holds an `Rc` to the same `RefCell<Mailbox>`, so a message
+/// posted by any of them is visible to all.
+#[derive(Debug)]
+pub struct RelayStation {
+    pub name: String,
+    mailbox: Rc<RefCell<Mailbox>>,
+}
+
+/// A CubeSat that knows which station it was connected through.
+///
+/// The link back is a `Weak` reference: the station owns the mailbox, but the
+/// satellite must not keep the station alive once it is decommissioned.
+#[derive(Debug, Clone)]
+pub struct LinkedSat {
+    pub sat: CubeSat,
+    station: Weak<RelayStation>,
+}
 
 impl Mailbox {
-    /// Adds a message to the mailbox.
-    fn post(&mut self, msg: Message) {
-        self.messages.push(msg);
+    /// Creates an empty mailbox.
+    pub fn new() -> Self {
+        Mailbox {
+            queues: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the total number of messages waiting in the mailbox.
+    pub fn len(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    /// Returns `true` if no messages are waiting.
+    pub fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+
+    /// Adds a message to the back of its recipient's queue.
+    pub fn post(&mut self, msg: Message) {
+        self.queues.entry(msg.to).or_default().push_back(msg);
     }
 
-    /// Delivers a message addressed to the given CubeSat, if available.
-    fn deliver(&mut self, recipient: &CubeSat) -> Option<Message> {
-        for i in (0..self.messages.len()).rev() {
-            if self.messages[i].to == recipient.id {
-                return Some(self.messages.remove(i));
-            }
+    /// Delivers the oldest message addressed to the given CubeSat, if available.
+    pub fn deliver(&mut self, recipient: &CubeSat) -> Option<Message> {
+        let queue = self.queues.get_mut(&recipient.id)?;
+        let msg = queue.pop_front();
+        if queue.is_empty() {
+            self.queues.remove(&recipient.id);
         }
-        None
+        msg
+    }
+
+    /// Returns the number of messages waiting for the given CubeSat.
+    pub fn pending_for(&self, sat_id: u64) -> usize {
+        self.queues.get(&sat_id).map_or(0, VecDeque::len)
+    }
+
+    /// Returns the message the given CubeSat would receive next, without removing it.
+    pub fn peek_for(&self, sat_id: u64) -> Option<&Message> {
+        self.queues.get(&sat_id)?.front()
+    }
+
+    /// The CubeSats with messages waiting, in ascending order.
+    pub fn recipients(&self) -> impl Iterator<Item = u64> + '_ {
+        self.queues.keys().copied()
+    }
+
+    /// Removes and returns every message for the given CubeSat, oldest first.
+    pub fn drain_for(&mut self, sat_id: u64) -> impl Iterator<Item = Message> {
+        self.queues.remove(&sat_id).unwrap_or_default().into_iter()
     }
 }
 
 impl GroundStation {
     /// Connects to a CubeSat with the given ID.
-    fn connect(&self, sat_id: u64) -> CubeSat {
+    pub fn connect(&self, sat_id: u64) -> CubeSat {
         CubeSat { id: sat_id }
     }
 
     /// Sends a message using a mutable reference to the mailbox.
     ///
     /// This avoids taking ownership of the mailbox and allows reuse.
-    fn send_with_reference(&self, mailbox: &mut Mailbox, msg: Message) {
+    pub fn send_with_reference(&self, mailbox: &mut Mailbox, msg: Message) {
         mailbox.post(msg);
     }
 
     /// Sends a message by cloning the mailbox and returning the modified copy.
     ///
     /// Useful when ownership cannot be transferred but modifications are needed.
-    fn send_with_clone(&self, mailbox: Mailbox, msg: Message) -> Mailbox {
+    pub fn send_with_clone(&self, mailbox: Mailbox, msg: Message) -> Mailbox {
         let mut mailbox_clone = mailbox.clone();
         mailbox_clone.post(msg);
         mailbox_clone
@@ -64,7 +160,7 @@ impl GroundStation {
     /// Sends a message by taking and returning ownership of the mailbox.
     ///
     /// This allows temporary ownership without cloning.
-    fn send_with_refactor(&self, mailbox: Mailbox, msg: Message) -> Mailbox {
+    pub fn send_with_refactor(&self, mailbox: Mailbox, msg: Message) -> Mailbox {
         let mut mailbox = mailbox;
         mailbox.post(msg);
         mailbox
@@ -72,104 +168,234 @@ impl GroundStation {
 
     /// Sends a message using an `Rc` for shared ownership.
     ///
-    /// Since `Mailbox::post` requires a mutable reference, we clone the `Rc`
-    /// and operate on the underlying data. This method assumes the mailbox is immutable
-    /// (not shared mutable). Use `Rc<RefCell<Mailbox>>` for shared mutability.
-    fn send_with_rc(&self, mailbox: Rc<Mailbox>, msg: Message) {
-        let mut mailbox_clone = mailbox.as_ref().clone();
-        mailbox_clone.post(msg);
+    /// `Rc` alone only hands out shared references, so the mailbox is wrapped in
+    /// a `RefCell` to borrow it mutably for the duration of the post. Every
+    /// other owner of the `Rc` sees the new message.
+    pub fn send_with_rc(&self, mailbox: Rc<RefCell<Mailbox>>, msg: Message) {
+        mailbox.borrow_mut().post(msg);
+    }
+}
+
+impl RelayStation {
+    /// Creates a station posting into `mailbox`, which it shares with its other owners.
+    pub fn new(name: &str, mailbox: &Rc<RefCell<Mailbox>>) -> Rc<RelayStation> {
+        Rc::new(RelayStation {
+            name: name.to_string(),
+            mailbox: Rc::clone(mailbox),
+        })
+    }
+
+    /// Connects to a CubeSat, which keeps a weak link back to this station.
+    pub fn connect(self: &Rc<Self>, sat_id: u64) -> LinkedSat {
+        LinkedSat {
+            sat: CubeSat { id: sat_id },
+            station: Rc::downgrade(self),
+        }
+    }
+
+    /// Posts a message into the shared mailbox.
+    pub fn send(&self, msg: Message) {
+        GroundStation.send_with_rc(Rc::clone(&self.mailbox), msg);
+    }
+}
+
+impl LinkedSat {
+    /// The station this satellite was connected through, if it still exists.
+    pub fn station(&self) -> Option<Rc<RelayStation>> {
+        self.station.upgrade()
+    }
+
+    /// Receives a message through the station's mailbox.
+    ///
+    /// Returns `None` if there is no message or the station is gone.
+    pub fn recv(&self) -> Option<Message> {
+        let station = self.station()?;
+        let mut mailbox = station.mailbox.borrow_mut();
+        self.sat.recv(&mut mailbox)
     }
 }
 
 impl CubeSat {
     /// Receives a message addressed to this CubeSat from the mailbox.
-    fn recv(&self, mailbox: &mut Mailbox) -> Option<Message> {
+    pub fn recv(&self, mailbox: &mut Mailbox) -> Option<Message> {
         mailbox.deliver(self)
     }
 
     /// Logs the number of messages in the mailbox for this CubeSat.
-    fn log_status(&self, mailbox: &Mailbox) {
-        let count = mailbox
-            .messages
-            .iter()
-            .filter(|msg| msg.to == self.id)
-            .count();
-        println!("CubeSat {} has {} pending messages.", self.id, count);
+    pub fn log_status(&self, mailbox: &Mailbox, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "CubeSat {} has {} pending messages.", self.id, mailbox.pending_for(self.id))
     }
 }
 
-/// Fetches a list of CubeSat IDs to be used in the simulation.
-fn fetch_sat_ids() -> Vec<u64> {
-    vec![1, 2, 3]
+/// Fetches a list of `count` CubeSat IDs to be used in the simulation.
+fn fetch_sat_ids(count: u64) -> Vec<u64> {
+    (1..=count).collect()
 }
 
-pub fn main() {
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
     let base = GroundStation {};
-    let sat_ids = fetch_sat_ids();
+    let sat_ids = fetch_sat_ids(sat_count);
 
     // Create an empty Mailbox
-    let mut mailbox = Mailbox {
-        messages: vec![],
-    };
+    let mut mailbox = Mailbox::new();
 
-    println!("=== Method 1: Using References ===");
+    writeln!(out, "=== Method 1: Using References ===")?;
+    let mut cost = Usage::default();
     for sat_id in sat_ids.iter() {
         let sat = base.connect(*sat_id);
         let msg = Message {
             to: *sat_id,
             content: format!("Hello, CubeSat {}!", sat_id),
         };
-        base.send_with_reference(&mut mailbox, msg);
-        sat.log_status(&mailbox);
+        let ((), usage) = alloc_count::measure(|| base.send_with_reference(&mut mailbox, msg));
+        cost += usage;
+        sat.log_status(&mailbox, out)?;
     }
-    println!("Mailbox after reference method: {:?}", mailbox);
+    writeln!(out, "Mailbox after reference method: {:?}", mailbox)?;
+    writeln!(out, "Cost: {}", cost)?;
 
-    println!("\n=== Method 2: Cloning ===");
+    writeln!(out, "\n=== Method 2: Cloning ===")?;
+    let mut cost = Usage::default();
     for sat_id in sat_ids.iter() {
         let sat = base.connect(*sat_id);
         let msg = Message {
             to: *sat_id,
             content: format!("Cloned msg for CubeSat {}!", sat_id),
         };
-        mailbox = base.send_with_clone(mailbox, msg);
-        sat.log_status(&mailbox);
+        // Passing `mailbox` by value is a move; the clone happens inside
+        let usage;
+        (mailbox, usage) = alloc_count::measure(|| base.send_with_clone(mailbox, msg));
+        cost += usage;
+        sat.log_status(&mailbox, out)?;
     }
-    println!("Mailbox after clone method: {:?}", mailbox);
+    writeln!(out, "Mailbox after clone method: {:?}", mailbox)?;
+    writeln!(out, "Cost: {}", cost)?;
 
-    println!("\n=== Method 3: Refactoring to Return Ownership ===");
+    writeln!(out, "\n=== Method 3: Refactoring to Return Ownership ===")?;
+    let mut cost = Usage::default();
     for sat_id in sat_ids.iter() {
         let sat = base.connect(*sat_id);
         let msg = Message {
             to: *sat_id,
             content: format!("Refactored msg for CubeSat {}!", sat_id),
         };
-        mailbox = base.send_with_refactor(mailbox, msg);
-        sat.log_status(&mailbox);
+        let usage;
+        (mailbox, usage) = alloc_count::measure(|| base.send_with_refactor(mailbox, msg));
+        cost += usage;
+        sat.log_status(&mailbox, out)?;
     }
-    println!("Mailbox after refactor method: {:?}", mailbox);
+    writeln!(out, "Mailbox after refactor method: {:?}", mailbox)?;
+    writeln!(out, "Cost: {}", cost)?;
 
-    println!("\n=== Method 4: Using Rc for Shared Ownership ===");
-    let mailbox_rc = Rc::new(mailbox);
-    for sat_id in sat_ids.iter() {
-        let sat = base.connect(*sat_id);
+    writeln!(out, "\n=== Method 4: Using Rc<RefCell> for Shared Ownership ===")?;
+    let mailbox_rc = Rc::new(RefCell::new(mailbox));
+    let stations = [
+        RelayStation::new("Houston", &mailbox_rc),
+        RelayStation::new("Kourou", &mailbox_rc),
+    ];
+    writeln!(
+        out,
+        "{} stations share the mailbox ({} owners)",
+        stations.len(),
+        Rc::strong_count(&mailbox_rc)
+    )?;
+
+    let mut sats = vec![];
+    let mut cost = Usage::default();
+    for (i, sat_id) in sat_ids.iter().enumerate() {
+        // Alternate stations; both post into the same mailbox
+        let station = &stations[i % stations.len()];
+        let sat = station.connect(*sat_id);
         let msg = Message {
             to: *sat_id,
-            content: format!("Rc msg for CubeSat {}!", sat_id),
+            content: format!("Rc msg from {} for CubeSat {}!", station.name, sat_id),
         };
-        base.send_with_rc(Rc::clone(&mailbox_rc), msg);
-        sat.log_status(mailbox_rc.as_ref());
-    }
-    println!("Mailbox after Rc method: {:?}", mailbox_rc);
-
-    println!("\n=== Receiving Messages ===");
-    // Clone Rc inner for mutable access to receive messages
-    let mut mailbox = mailbox_rc.as_ref().clone();
-    for sat_id in sat_ids {
-        let sat = base.connect(sat_id);
-        if let Some(msg) = sat.recv(&mut mailbox) {
-            println!("CubeSat {} received: {:?}", sat_id, msg);
+        let ((), usage) = alloc_count::measure(|| station.send(msg));
+        cost += usage;
+        sat.sat.log_status(&mailbox_rc.borrow(), out)?;
+        sats.push(sat);
+    }
+    base.send_with_rc(
+        Rc::clone(&mailbox_rc),
+        Message {
+            to: sat_ids[0],
+            content: format!("Rc msg from base for CubeSat {}!", sat_ids[0]),
+        },
+    );
+    sats[0].sat.log_status(&mailbox_rc.borrow(), out)?;
+    writeln!(out, "Mailbox after Rc method: {:?}", mailbox_rc.borrow())?;
+    writeln!(out, "Cost of the station sends: {}", cost)?;
+    if let Some(msg) = mailbox_rc.borrow().peek_for(sat_ids[0]) {
+        writeln!(out, "Next up for CubeSat {}: {}", sat_ids[0], msg.content)?;
+    }
+
+    writeln!(out, "\n=== Receiving Messages ===")?;
+    // Each satellite reaches the mailbox through its station's weak link
+    for sat in &sats {
+        let station = sat.station().map(|station| station.name.clone()).unwrap_or_default();
+        if let Some(msg) = sat.recv() {
+            writeln!(out, "CubeSat {} received via {}: {:?}", sat.sat.id, station, msg)?;
         } else {
-            println!("CubeSat {}: No messages.", sat_id);
+            writeln!(out, "CubeSat {}: No messages.", sat.sat.id)?;
         }
     }
+
+    writeln!(out, "\n=== Draining Leftovers ===")?;
+    for sat_id in sat_ids.iter() {
+        let leftovers: Vec<Message> = mailbox_rc.borrow_mut().drain_for(*sat_id).collect();
+        let newest = leftovers.last().map_or("-", |msg| msg.content.as_str());
+        writeln!(out, "CubeSat {} drained {} messages, newest: {}", sat_id, leftovers.len(), newest)?;
+    }
+    writeln!(out, "Mailbox empty: {}", mailbox_rc.borrow().is_empty())?;
+
+    writeln!(out, "\n=== Decommissioning Stations ===")?;
+    drop(stations);
+    writeln!(out, "Mailbox owners left: {}", Rc::strong_count(&mailbox_rc))?;
+    match sats[0].station() {
+        Some(station) => writeln!(out, "CubeSat {} still reports to {}", sats[0].sat.id, station.name)?,
+        None => writeln!(out, "CubeSat {} has no station to report to", sats[0].sat.id)?,
+    }
+
+    Ok(())
 }
+
+/// Knobs for the simulation.
+const PARAMS: &[Param] = &[
+    Param {
+        name: "sats",
+        help: "Number of CubeSats in the simulation",
+        kind: ParamKind::Integer { min: 1, max: 1000 },
+        default: "3",
+    },
+    Param {
+        name: "mode",
+        help: "Which simulation to run",
+        kind: ParamKind::Choice {
+            options: &["methods", "threaded", "network", "reliable", "scheduled", "broadcast", "durable", "trace"],
+        },
+        default: "methods",
+    },
+];
+
+/// Registry entry for the `own` demo.
+pub const DEMO: Entry = Entry {
+    name: "own",
+    category: Category::Concepts,
+    summary: "Ownership strategies for a shared mailbox: references, clones, moves, `Rc<RefCell>` and `Weak`.",
+    prerequisites: &["strings", "control_flow"],
+    params: PARAMS,
+    run: |params, out| {
+        let sats = params.integer("sats");
+        match params.choice("mode") {
+            "threaded" => threaded::main(sats, out),
+            "network" => protocol::main(sats, out),
+            "reliable" => reliable::main(sats, out),
+            "scheduled" => schedule::main(sats, out),
+            "broadcast" => broadcast::main(sats, out),
+            "durable" => durable::main(sats, out),
+            "trace" => trace::main(sats, out),
+            _ => main(sats, out),
+        }
+    },
+    ..Entry::DEFAULTS
+};
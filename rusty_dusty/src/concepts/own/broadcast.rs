@@ -0,0 +1,354 @@
+//! # Broadcast and Group Addressing
+//!
+//! Sending the same text to every CubeSat through a [`Mailbox`](super::Mailbox)
+//! means one `Message`, and one `String` clone, per satellite. A
+//! [`BroadcastMailbox`] addresses a single post to one CubeSat, a named group
+//! or all of them, and stores its content once: each recipient's queue holds
+//! another handle to the same `Rc<str>`, which costs a reference count bump
+//! rather than a copy. `BroadcastMailbox<Arc<str>>` does the same for a mailbox
+//! shared between threads.
+//!
+//! Every post gets a [`Receipt`] recording which recipients have taken it.
+//! Receipts are kept until [`BroadcastMailbox::take_completed`] collects the
+//! ones every recipient has.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use super::{fetch_sat_ids, CubeSat, Mailbox, Message};
+use crate::alloc_count;
+
+/// Who a post is for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    Sat(u64),
+    Group(String),
+    /// Every registered CubeSat.
+    All,
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Address::Sat(id) => write!(f, "CubeSat {}", id),
+            Address::Group(name) => write!(f, "group `{}`", name),
+            Address::All => write!(f, "all CubeSats"),
+        }
+    }
+}
+
+/// Identifies a post for looking up its receipt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PostId(pub u64);
+
+/// Errors from addressing a post.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    UnknownSat(u64),
+    UnknownGroup(String),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressError::UnknownSat(id) => write!(f, "CubeSat {} is not registered", id),
+            AddressError::UnknownGroup(name) => write!(f, "no group named `{}`", name),
+        }
+    }
+}
+
+/// Which recipients of a post have taken delivery of it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Receipt {
+    pub recipients: BTreeSet<u64>,
+    pub delivered: BTreeSet<u64>,
+}
+
+impl Receipt {
+    /// Recipients still waiting for the post, in ascending order.
+    pub fn pending(&self) -> impl Iterator<Item = u64> + '_ {
+        self.recipients.difference(&self.delivered).copied()
+    }
+
+    /// `true` once every recipient has the post.
+    pub fn is_complete(&self) -> bool {
+        self.delivered.len() == self.recipients.len()
+    }
+}
+
+/// A mailbox whose posts can be addressed to groups, with shared content.
+///
+/// `P` is the shared string type: `Rc<str>` by default, or `Arc<str>` for a
+/// mailbox used from several threads.
+#[derive(Debug)]
+pub struct BroadcastMailbox<P = Rc<str>> {
+    sats: BTreeSet<u64>,
+    groups: BTreeMap<String, BTreeSet<u64>>,
+    queues: BTreeMap<u64, VecDeque<(PostId, P)>>,
+    receipts: BTreeMap<PostId, Receipt>,
+    next_id: u64,
+}
+
+impl<P> Default for BroadcastMailbox<P> {
+    fn default() -> Self {
+        BroadcastMailbox {
+            sats: BTreeSet::new(),
+            groups: BTreeMap::new(),
+            queues: BTreeMap::new(),
+            receipts: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+}
+
+impl<P: Clone + Deref<Target = str>> BroadcastMailbox<P> {
+    pub fn new() -> Self {
+        BroadcastMailbox::default()
+    }
+
+    /// Registers a CubeSat so it can be addressed and receives broadcasts.
+    pub fn register(&mut self, sat_id: u64) {
+        self.sats.insert(sat_id);
+    }
+
+    /// Adds a registered CubeSat to a group, creating the group if needed.
+    pub fn join(&mut self, group: &str, sat_id: u64) -> Result<(), AddressError> {
+        if !self.sats.contains(&sat_id) {
+            return Err(AddressError::UnknownSat(sat_id));
+        }
+        self.groups.entry(group.to_string()).or_default().insert(sat_id);
+        Ok(())
+    }
+
+    /// The CubeSats an address currently resolves to.
+    pub fn resolve(&self, to: &Address) -> Result<BTreeSet<u64>, AddressError> {
+        match to {
+            Address::Sat(id) if self.sats.contains(id) => Ok(BTreeSet::from([*id])),
+            Address::Sat(id) => Err(AddressError::UnknownSat(*id)),
+            Address::Group(name) => self
+                .groups
+                .get(name)
+                .cloned()
+                .ok_or_else(|| AddressError::UnknownGroup(name.clone())),
+            Address::All => Ok(self.sats.clone()),
+        }
+    }
+
+    /// Queues one post for every CubeSat the address resolves to.
+    ///
+    /// The content is converted to `P` once; each queue gets a clone of that
+    /// handle, not of the text.
+    pub fn post(&mut self, to: &Address, content: impl Into<P>) -> Result<PostId, AddressError> {
+        let recipients = self.resolve(to)?;
+        let id = PostId(self.next_id);
+        self.next_id += 1;
+
+        let content = content.into();
+        for &sat_id in &recipients {
+            self.queues.entry(sat_id).or_default().push_back((id, content.clone()));
+        }
+        self.receipts.insert(
+            id,
+            Receipt {
+                recipients,
+                delivered: BTreeSet::new(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Delivers the oldest post waiting for a CubeSat and records the delivery.
+    pub fn deliver(&mut self, recipient: &CubeSat) -> Option<(PostId, P)> {
+        let queue = self.queues.get_mut(&recipient.id)?;
+        let (id, content) = queue.pop_front()?;
+        if queue.is_empty() {
+            self.queues.remove(&recipient.id);
+        }
+        if let Some(receipt) = self.receipts.get_mut(&id) {
+            receipt.delivered.insert(recipient.id);
+        }
+        Some((id, content))
+    }
+
+    /// Which recipients of a post have taken it so far.
+    pub fn receipt(&self, id: PostId) -> Option<&Receipt> {
+        self.receipts.get(&id)
+    }
+
+    /// Removes and returns the receipts of posts every recipient has taken.
+    pub fn take_completed(&mut self) -> Vec<(PostId, Receipt)> {
+        let completed: Vec<PostId> = self
+            .receipts
+            .iter()
+            .filter(|(_, receipt)| receipt.is_complete())
+            .map(|(&id, _)| id)
+            .collect();
+        completed
+            .into_iter()
+            .filter_map(|id| self.receipts.remove_entry(&id))
+            .collect()
+    }
+
+    /// Returns the number of posts waiting for the given CubeSat.
+    pub fn pending_for(&self, sat_id: u64) -> usize {
+        self.queues.get(&sat_id).map_or(0, VecDeque::len)
+    }
+}
+
+/// Sends one text to every CubeSat, comparing per-recipient clones with shared content.
+///
+/// Each strategy sends twice and only the second send is counted, so the
+/// queues already exist and the numbers show the cost of the content alone.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    const CONTENT: &str = "Firmware update 2.1 is available";
+    let sat_ids = fetch_sat_ids(sat_count);
+
+    writeln!(out, "=== Addressing ===")?;
+    let mut mailbox: BroadcastMailbox = BroadcastMailbox::new();
+    for &sat_id in &sat_ids {
+        mailbox.register(sat_id);
+        if sat_id.is_multiple_of(2) {
+            mailbox.join("even", sat_id).map_err(|err| io::Error::other(err.to_string()))?;
+        }
+    }
+    let content: Rc<str> = Rc::from(CONTENT);
+    for to in [Address::All, Address::Group("even".to_string()), Address::Sat(1)] {
+        match mailbox.post(&to, Rc::clone(&content)) {
+            Ok(id) => writeln!(out, "Post {} to {} reaches {:?}", id.0, to, mailbox.resolve(&to).unwrap_or_default())?,
+            Err(err) => writeln!(out, "Post to {} failed: {}", to, err)?,
+        }
+    }
+    writeln!(out, "{} handles share one copy of the content", Rc::strong_count(&content))?;
+
+    let first = CubeSat { id: sat_ids[0] };
+    while let Some((id, text)) = mailbox.deliver(&first) {
+        writeln!(out, "CubeSat {} received post {}: {}", first.id, id.0, text)?;
+    }
+    if let Some(receipt) = mailbox.receipt(PostId(1)) {
+        writeln!(out, "Post 1 still pending for {:?}", receipt.pending().collect::<Vec<_>>())?;
+    }
+
+    writeln!(out, "\n=== Allocations per Send to {} CubeSats ===", sat_ids.len())?;
+    let mut cloned = Mailbox::new();
+    let mut send_cloned = || {
+        let content = CONTENT.to_string();
+        for &sat_id in &sat_ids {
+            cloned.post(Message {
+                to: sat_id,
+                content: content.clone(),
+            });
+        }
+    };
+    send_cloned();
+    let ((), allocations) = alloc_count::count(send_cloned);
+    writeln!(out, "String cloned per recipient: {}", allocations)?;
+
+    let mut shared: BroadcastMailbox = BroadcastMailbox::new();
+    let mut threaded: BroadcastMailbox<Arc<str>> = BroadcastMailbox::new();
+    for &sat_id in &sat_ids {
+        shared.register(sat_id);
+        threaded.register(sat_id);
+    }
+    shared.post(&Address::All, CONTENT).ok();
+    let (_, allocations) = alloc_count::count(|| shared.post(&Address::All, CONTENT));
+    writeln!(out, "Rc<str> broadcast:           {}", allocations)?;
+    threaded.post(&Address::All, CONTENT).ok();
+    let (_, allocations) = alloc_count::count(|| threaded.post(&Address::All, CONTENT));
+    writeln!(out, "Arc<str> broadcast:          {}", allocations)?;
+    writeln!(out, "A broadcast copies its content once; the rest is the receipt's set of recipients.")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    fn mailbox() -> BroadcastMailbox {
+        let mut mailbox = BroadcastMailbox::new();
+        for id in 1..=4 {
+            mailbox.register(id);
+        }
+        mailbox.join("polar", 2).unwrap();
+        mailbox.join("polar", 4).unwrap();
+        mailbox
+    }
+
+    #[test]
+    fn test_addresses_resolve_to_recipients() {
+        let mut mailbox = mailbox();
+        mailbox.post(&Address::All, "everyone").unwrap();
+        mailbox.post(&Address::Group("polar".to_string()), "polar only").unwrap();
+        mailbox.post(&Address::Sat(3), "just 3").unwrap();
+
+        let pending: Vec<usize> = (1..=4).map(|id| mailbox.pending_for(id)).collect();
+        assert_eq!(pending, [1, 2, 2, 2]);
+        assert_eq!(mailbox.post(&Address::Sat(9), "lost"), Err(AddressError::UnknownSat(9)));
+        assert_eq!(
+            mailbox.post(&Address::Group("equatorial".to_string()), "lost").unwrap_err().to_string(),
+            "no group named `equatorial`"
+        );
+        assert_eq!(mailbox.join("polar", 9), Err(AddressError::UnknownSat(9)));
+    }
+
+    #[test]
+    fn test_broadcast_shares_content_and_tracks_receipts() {
+        let mut mailbox = mailbox();
+        let content: Rc<str> = Rc::from("shared");
+        let id = mailbox.post(&Address::All, Rc::clone(&content)).unwrap();
+        assert_eq!(Rc::strong_count(&content), 5);
+
+        let (delivered, text) = mailbox.deliver(&CubeSat { id: 2 }).unwrap();
+        assert_eq!(delivered, id);
+        assert!(Rc::ptr_eq(&text, &content));
+
+        let receipt = mailbox.receipt(id).unwrap();
+        assert_eq!(receipt.pending().collect::<Vec<_>>(), [1, 3, 4]);
+        assert!(!receipt.is_complete());
+    }
+
+    #[test]
+    fn test_arc_mailbox_is_shared_across_threads() {
+        let mut mailbox: BroadcastMailbox<Arc<str>> = BroadcastMailbox::new();
+        for id in 1..=4 {
+            mailbox.register(id);
+        }
+        let id = mailbox.post(&Address::All, "to every thread").unwrap();
+
+        let mailbox = Arc::new(Mutex::new(mailbox));
+        let handles: Vec<_> = (1..=4)
+            .map(|sat_id| {
+                let mailbox = Arc::clone(&mailbox);
+                thread::spawn(move || mailbox.lock().unwrap().deliver(&CubeSat { id: sat_id }))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(&*handle.join().unwrap().unwrap().1, "to every thread");
+        }
+        assert!(mailbox.lock().unwrap().receipt(id).unwrap().is_complete());
+    }
+
+    #[test]
+    fn test_completed_receipts_are_taken_once() {
+        let mut mailbox = mailbox();
+        let everyone = mailbox.post(&Address::All, "everyone").unwrap();
+        let polar = mailbox.post(&Address::Group("polar".to_string()), "polar only").unwrap();
+        mailbox.deliver(&CubeSat { id: 2 });
+        mailbox.deliver(&CubeSat { id: 4 });
+        mailbox.deliver(&CubeSat { id: 4 });
+        assert!(mailbox.take_completed().is_empty());
+
+        mailbox.deliver(&CubeSat { id: 2 });
+        let taken = mailbox.take_completed();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].0, polar);
+        assert!(mailbox.receipt(polar).is_none());
+        assert!(mailbox.receipt(everyone).is_some());
+        assert!(mailbox.take_completed().is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+//! # Durable Mailbox
+//!
+//! A [`Mailbox`] backed by an append-only log, so queued messages survive a
+//! restart. Every `post` and `deliver` is appended to the log before it is
+//! applied in memory; opening the mailbox replays the log to rebuild it.
+//!
+//! Each record is framed as a big-endian `u32` body length, a CRC-32 of the body
+//! and the body itself. The body is a one-byte tag followed by:
+//!
+//! - `1` Post: the recipient as a big-endian `u64`, then the content as UTF-8.
+//! - `2` Deliver: the CubeSat `id` as a big-endian `u64`.
+//!
+//! A crash part way through an append can only damage the last record, so a
+//! last record that is cut short or fails its checksum is dropped, and the log
+//! is truncated there so later appends start from a clean record. Damage
+//! anywhere else means the file itself is bad: opening it fails with
+//! `InvalidData` and the log is left as it is. Since
+//! delivered messages leave dead records behind, the log is compacted down to
+//! just the pending messages once enough of them pile up.
+//!
+//! Records are written straight to the file without `fsync`, which protects
+//! against the process dying but not against losing power.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use super::{fetch_sat_ids, CubeSat, Mailbox, Message};
+
+const POST: u8 = 1;
+const DELIVER: u8 = 2;
+
+/// Length and checksum in front of every record body.
+const HEADER: usize = 8;
+
+/// Dead records tolerated before the log is compacted, by default.
+pub const DEFAULT_COMPACT_THRESHOLD: usize = 1024;
+
+/// One entry in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Record {
+    Post(Message),
+    Deliver(u64),
+}
+
+impl Record {
+    fn encode(&self) -> Vec<u8> {
+        let mut body = vec![];
+        match self {
+            Record::Post(msg) => {
+                body.push(POST);
+                body.extend_from_slice(&msg.to.to_be_bytes());
+                body.extend_from_slice(msg.content.as_bytes());
+            }
+            Record::Deliver(sat_id) => {
+                body.push(DELIVER);
+                body.extend_from_slice(&sat_id.to_be_bytes());
+            }
+        }
+
+        let mut record = Vec::with_capacity(HEADER + body.len());
+        record.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        record.extend_from_slice(&crc32(&body).to_be_bytes());
+        record.extend_from_slice(&body);
+        record
+    }
+
+    fn decode(body: &[u8]) -> Option<Record> {
+        let (&tag, rest) = body.split_first()?;
+        if rest.len() < 8 {
+            return None;
+        }
+        let (id, rest) = rest.split_at(8);
+        let id = u64::from_be_bytes(id.try_into().ok()?);
+        match tag {
+            POST => Some(Record::Post(Message {
+                to: id,
+                content: String::from_utf8(rest.to_vec()).ok()?,
+            })),
+            DELIVER if rest.is_empty() => Some(Record::Deliver(id)),
+            _ => None,
+        }
+    }
+}
+
+/// What opening the log found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recovery {
+    /// Complete, valid records replayed.
+    pub records: usize,
+    /// Bytes dropped from the end of the log.
+    pub discarded_bytes: u64,
+    /// `true` if the last record failed its checksum, rather than just being cut short.
+    pub corrupt: bool,
+}
+
+/// A mailbox whose contents are kept in a log file.
+#[derive(Debug)]
+pub struct DurableMailbox {
+    path: PathBuf,
+    log: File,
+    mailbox: Mailbox,
+    /// Records currently in the log, live or dead.
+    records: usize,
+    /// The log's length after the last complete record.
+    log_len: u64,
+    compact_threshold: usize,
+    recovery: Recovery,
+}
+
+impl DurableMailbox {
+    /// Opens the log at `path`, creating it if needed, and replays it.
+    ///
+    /// Fails with `InvalidData` if a record before the last one is damaged.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<DurableMailbox> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let mut bytes = vec![];
+        log.read_to_end(&mut bytes)?;
+        let (mailbox, valid, mut recovery) = replay(&bytes).map_err(|offset| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: damaged record at byte {}", path.display(), offset),
+            )
+        })?;
+
+        recovery.discarded_bytes = (bytes.len() - valid) as u64;
+        if valid < bytes.len() {
+            log.set_len(valid as u64)?;
+        }
+        log.seek(SeekFrom::End(0))?;
+
+        Ok(DurableMailbox {
+            path,
+            log,
+            mailbox,
+            records: recovery.records,
+            log_len: valid as u64,
+            compact_threshold: DEFAULT_COMPACT_THRESHOLD,
+            recovery,
+        })
+    }
+
+    /// What was found when the log was opened.
+    pub fn recovery(&self) -> &Recovery {
+        &self.recovery
+    }
+
+    /// The pending messages.
+    pub fn mailbox(&self) -> &Mailbox {
+        &self.mailbox
+    }
+
+    /// Sets how many dead records may pile up before the log is compacted.
+    pub fn set_compact_threshold(&mut self, threshold: usize) {
+        self.compact_threshold = threshold;
+    }
+
+    /// The number of records in the log, including delivered messages.
+    pub fn log_records(&self) -> usize {
+        self.records
+    }
+
+    /// Logs and queues a message.
+    pub fn post(&mut self, msg: Message) -> io::Result<()> {
+        self.append(&Record::Post(msg.clone()))?;
+        self.mailbox.post(msg);
+        Ok(())
+    }
+
+    /// Delivers the oldest message for a CubeSat, logging the delivery first.
+    pub fn deliver(&mut self, recipient: &CubeSat) -> io::Result<Option<Message>> {
+        if self.mailbox.peek_for(recipient.id).is_none() {
+            return Ok(None);
+        }
+        self.append(&Record::Deliver(recipient.id))?;
+        let msg = self.mailbox.deliver(recipient);
+        self.compact_if_needed()?;
+        Ok(msg)
+    }
+
+    /// Rewrites the log to hold only the pending messages.
+    ///
+    /// The new log is written beside the old one and renamed over it, so a
+    /// crash part way through leaves the old log intact.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let temp = self.path.with_extension("compact");
+        let mut bytes = vec![];
+        let mut records = 0;
+        let mut pending = self.mailbox.clone();
+        let sat_ids: Vec<u64> = pending.recipients().collect();
+        for sat_id in sat_ids {
+            for msg in pending.drain_for(sat_id) {
+                bytes.extend_from_slice(&Record::Post(msg).encode());
+                records += 1;
+            }
+        }
+
+        let mut file = File::create(&temp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&temp, &self.path)?;
+
+        self.log = OpenOptions::new().append(true).open(&self.path)?;
+        self.records = records;
+        self.log_len = bytes.len() as u64;
+        Ok(())
+    }
+
+    fn compact_if_needed(&mut self) -> io::Result<()> {
+        let dead = self.records - self.mailbox.len();
+        if dead >= self.compact_threshold && dead > self.mailbox.len() {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Appends a record, cutting off any partial write if it fails.
+    fn append(&mut self, record: &Record) -> io::Result<()> {
+        let bytes = record.encode();
+        if let Err(err) = self.log.write_all(&bytes) {
+            let _ = self.log.set_len(self.log_len);
+            return Err(err);
+        }
+        self.records += 1;
+        self.log_len += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+/// Rebuilds a mailbox from log bytes.
+///
+/// Returns the mailbox, the length of the valid prefix of the log and what
+/// was found, or the offset of a damaged record that is not the last one.
+fn replay(bytes: &[u8]) -> Result<(Mailbox, usize, Recovery), usize> {
+    let mut mailbox = Mailbox::new();
+    let mut recovery = Recovery::default();
+    let mut offset = 0;
+
+    while let Some((record, len)) = read_record(&bytes[offset..]) {
+        let end = offset + HEADER + len;
+        let Some(record) = record else {
+            // A record cut short by a crash runs past the end of the log, but
+            // so does one whose length is damaged; only the first has nothing
+            // valid after it
+            if end < bytes.len() || (end > bytes.len() && ends_with_records(&bytes[offset + 1..])) {
+                return Err(offset);
+            }
+            recovery.corrupt = end == bytes.len();
+            break;
+        };
+
+        match record {
+            Record::Post(msg) => mailbox.post(msg),
+            Record::Deliver(sat_id) => {
+                mailbox.deliver(&CubeSat { id: sat_id });
+            }
+        }
+        recovery.records += 1;
+        offset = end;
+    }
+
+    Ok((mailbox, offset, recovery))
+}
+
+/// Reads the record at the start of `bytes`, if there is a whole header.
+///
+/// Returns the record, or `None` if it is cut short or fails its checksum,
+/// along with the body length its header claims.
+fn read_record(bytes: &[u8]) -> Option<(Option<Record>, usize)> {
+    let header = bytes.first_chunk::<HEADER>()?;
+    let len = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+    let checksum = u32::from_be_bytes(header[4..].try_into().unwrap());
+    let record = bytes
+        .get(HEADER..HEADER + len)
+        .filter(|body| crc32(body) == checksum)
+        .and_then(Record::decode);
+    Some((record, len))
+}
+
+/// Returns `true` if `bytes` ends with one or more valid records back to back.
+///
+/// Walks backwards, so a record is only checksummed if it would end exactly
+/// where a run of valid records starts. Bytes inside a message that happen to
+/// look like a record don't count unless they also line up with the end.
+fn ends_with_records(bytes: &[u8]) -> bool {
+    // `chained[i]` is whether valid records run from `i` to the end
+    let mut chained = vec![false; bytes.len() + 1];
+    chained[bytes.len()] = true;
+    for start in (0..bytes.len()).rev() {
+        let Some(header) = bytes[start..].first_chunk::<HEADER>() else {
+            continue;
+        };
+        let len = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+        let end = start + HEADER + len;
+        chained[start] = end <= bytes.len()
+            && chained[end]
+            && matches!(read_record(&bytes[start..]), Some((Some(_), _)));
+    }
+    chained[..bytes.len()].contains(&true)
+}
+
+/// The CRC-32 (IEEE) checksum of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Queues a message for every CubeSat in a log file, then reopens the log as
+/// if after a restart and delivers what survived. One more post is cut short
+/// to stand in for a crash partway through a write.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    let path = std::env::temp_dir().join(format!("rusty_dusty_own_{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    let sat_ids = fetch_sat_ids(sat_count);
+
+    writeln!(out, "=== Before the Restart ===")?;
+    {
+        let mut mailbox = DurableMailbox::open(&path)?;
+        for &sat_id in &sat_ids {
+            mailbox.post(Message {
+                to: sat_id,
+                content: format!("Durable msg for CubeSat {}!", sat_id),
+            })?;
+        }
+        let first = CubeSat { id: sat_ids[0] };
+        if let Some(msg) = mailbox.deliver(&first)? {
+            writeln!(out, "CubeSat {} received: {}", first.id, msg.content)?;
+        }
+        writeln!(
+            out,
+            "{} messages pending in {} log records",
+            mailbox.mailbox().len(),
+            mailbox.log_records()
+        )?;
+    }
+
+    let torn = Record::Post(Message {
+        to: sat_ids[0],
+        content: "Never finished writing".to_string(),
+    })
+    .encode();
+    OpenOptions::new().append(true).open(&path)?.write_all(&torn[..HEADER + 4])?;
+    writeln!(out, "Crashed after writing {} of {} bytes of another post", HEADER + 4, torn.len())?;
+
+    writeln!(out, "\n=== After the Restart ===")?;
+    let mut mailbox = DurableMailbox::open(&path)?;
+    let recovery = mailbox.recovery().clone();
+    writeln!(
+        out,
+        "Replayed {} records, dropped {} bytes from the end of the log",
+        recovery.records, recovery.discarded_bytes
+    )?;
+    for &sat_id in &sat_ids {
+        let sat = CubeSat { id: sat_id };
+        while let Some(msg) = mailbox.deliver(&sat)? {
+            writeln!(out, "CubeSat {} received: {}", sat_id, msg.content)?;
+        }
+    }
+    writeln!(out, "Mailbox empty: {}", mailbox.mailbox().is_empty())?;
+    drop(mailbox);
+    fs::remove_file(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_records_round_trip() {
+        for record in [
+            Record::Post(Message {
+                to: 3,
+                content: "héllo".to_string(),
+            }),
+            Record::Deliver(3),
+        ] {
+            let bytes = record.encode();
+            assert_eq!(Record::decode(&bytes[HEADER..]), Some(record));
+        }
+    }
+
+    #[test]
+    fn test_record_inside_a_torn_write_is_not_mistaken_for_a_damaged_length() {
+        let first = Record::Post(Message {
+            to: 1,
+            content: "first".to_string(),
+        });
+        let mut body = vec![POST];
+        body.extend_from_slice(&1u64.to_be_bytes());
+        body.extend_from_slice(&Record::Deliver(1).encode());
+        body.extend_from_slice(b"tail");
+
+        let mut bytes = first.encode();
+        bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&crc32(&body).to_be_bytes());
+        bytes.extend_from_slice(&body);
+        bytes.truncate(bytes.len() - 2);
+
+        let (mailbox, valid, recovery) = replay(&bytes).unwrap();
+        assert_eq!(mailbox.len(), 1);
+        assert_eq!(valid, first.encode().len());
+        assert_eq!(recovery.records, 1);
+    }
+}
@@ -0,0 +1,615 @@
+//! # Ground-Station Protocol
+//!
+//! Carries the `own` demo's [`Message`]s over a real TCP connection. A
+//! [`GroundServer`] listens on localhost; each [`SatClient`] connects, registers
+//! its CubeSat `id` and then receives the messages addressed to it. Messages for
+//! a CubeSat that is not connected wait in the server's [`Mailbox`] and are
+//! flushed, oldest first, when it (re)connects.
+//!
+//! Every frame is a big-endian `u32` length followed by that many bytes of body.
+//! The body starts with a one-byte tag:
+//!
+//! - `1` Register: the CubeSat's `id` as a big-endian `u64`.
+//! - `2` Message: the recipient `to` as a big-endian `u64`, then the `content`
+//!   as UTF-8 filling the rest of the frame.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::{fetch_sat_ids, Mailbox, Message};
+
+/// The largest frame body accepted, to stop a bad length prefix from
+/// allocating without bound.
+pub const MAX_FRAME: usize = 64 * 1024;
+
+/// How long a new connection has to send its `Register` frame.
+pub const REGISTER_TIMEOUT: Duration = Duration::from_secs(1);
+
+const REGISTER: u8 = 1;
+const MESSAGE: u8 = 2;
+
+/// One unit of the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// Sent by a CubeSat as the first frame on a connection.
+    Register { sat_id: u64 },
+    /// A message for the CubeSat on the other end.
+    Message(Message),
+}
+
+/// Ways a frame can be malformed.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FrameError {
+    TooLarge(usize),
+    Empty,
+    UnknownTag(u8),
+    Truncated { tag: u8, len: usize },
+    InvalidUtf8,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameError::TooLarge(len) => write!(f, "frame of {} bytes exceeds {} bytes", len, MAX_FRAME),
+            FrameError::Empty => write!(f, "empty frame"),
+            FrameError::UnknownTag(tag) => write!(f, "unknown frame tag {}", tag),
+            FrameError::Truncated { tag, len } => write!(f, "frame tag {} too short at {} bytes", tag, len),
+            FrameError::InvalidUtf8 => write!(f, "message content is not UTF-8"),
+        }
+    }
+}
+
+impl From<FrameError> for io::Error {
+    fn from(err: FrameError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+    }
+}
+
+impl Frame {
+    /// Encodes the frame, length prefix included.
+    pub fn encode(&self) -> Result<Vec<u8>, FrameError> {
+        let mut body = vec![];
+        match self {
+            Frame::Register { sat_id } => {
+                body.push(REGISTER);
+                body.extend_from_slice(&sat_id.to_be_bytes());
+            }
+            Frame::Message(msg) => {
+                body.push(MESSAGE);
+                body.extend_from_slice(&msg.to.to_be_bytes());
+                body.extend_from_slice(msg.content.as_bytes());
+            }
+        }
+        if body.len() > MAX_FRAME {
+            return Err(FrameError::TooLarge(body.len()));
+        }
+
+        let mut frame = (body.len() as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(&body);
+        Ok(frame)
+    }
+
+    /// Decodes a frame body, without its length prefix.
+    fn decode(body: &[u8]) -> Result<Frame, FrameError> {
+        let (&tag, rest) = body.split_first().ok_or(FrameError::Empty)?;
+        let truncated = FrameError::Truncated { tag, len: body.len() };
+        match tag {
+            REGISTER => {
+                let sat_id = rest.try_into().map_err(|_| truncated)?;
+                Ok(Frame::Register {
+                    sat_id: u64::from_be_bytes(sat_id),
+                })
+            }
+            MESSAGE => {
+                if rest.len() < 8 {
+                    return Err(truncated);
+                }
+                let (to, content) = rest.split_at(8);
+                Ok(Frame::Message(Message {
+                    to: u64::from_be_bytes(to.try_into().unwrap()),
+                    content: String::from_utf8(content.to_vec()).map_err(|_| FrameError::InvalidUtf8)?,
+                }))
+            }
+            other => Err(FrameError::UnknownTag(other)),
+        }
+    }
+}
+
+/// Reassembles frames from bytes that may arrive in arbitrary pieces.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Decoder::default()
+    }
+
+    /// Adds bytes as they were read from the connection.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete frame, or `None` if more bytes are needed.
+    pub fn next_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        let Some(prefix) = self.buffer.first_chunk::<4>() else {
+            return Ok(None);
+        };
+        let len = u32::from_be_bytes(*prefix) as usize;
+        if len > MAX_FRAME {
+            return Err(FrameError::TooLarge(len));
+        }
+        if self.buffer.len() < 4 + len {
+            return Ok(None);
+        }
+
+        let frame = Frame::decode(&self.buffer[4..4 + len]);
+        self.buffer.drain(..4 + len);
+        frame.map(Some)
+    }
+
+    /// Returns `true` if no partial frame is buffered.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+}
+
+/// Writes one frame.
+pub fn write_frame(out: &mut dyn Write, frame: &Frame) -> io::Result<()> {
+    out.write_all(&frame.encode()?)?;
+    out.flush()
+}
+
+/// Reads whole frames from a byte stream.
+pub struct FrameReader<R> {
+    input: R,
+    decoder: Decoder,
+}
+
+impl<R: Read> FrameReader<R> {
+    pub fn new(input: R) -> Self {
+        FrameReader {
+            input,
+            decoder: Decoder::new(),
+        }
+    }
+
+    /// Reads the next frame, or `None` if the stream ended cleanly between frames.
+    pub fn read_frame(&mut self) -> io::Result<Option<Frame>> {
+        let mut chunk = [0u8; 4096];
+        loop {
+            if let Some(frame) = self.decoder.next_frame()? {
+                return Ok(Some(frame));
+            }
+            match self.input.read(&mut chunk)? {
+                0 if self.decoder.is_empty() => return Ok(None),
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                read => self.decoder.push(&chunk[..read]),
+            }
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.input
+    }
+}
+
+/// What happened to a message handed to [`GroundServer::send`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Written to the CubeSat's connection.
+    Sent,
+    /// The CubeSat is not connected; the message waits in the mailbox.
+    Queued,
+}
+
+/// A registered CubeSat connection.
+struct Link {
+    /// Tells a replaced connection apart from the one that replaced it.
+    connection: u64,
+    /// Locked on its own while writing, so a slow CubeSat only holds up
+    /// messages for itself.
+    stream: Arc<Mutex<TcpStream>>,
+}
+
+/// State shared between the server handle and its connection threads.
+#[derive(Default)]
+struct Links {
+    mailbox: Mailbox,
+    links: HashMap<u64, Link>,
+    /// Every open stream and its thread, by connection, so shutdown can close
+    /// and join them. A connection's thread removes it when it ends.
+    connections: HashMap<u64, (TcpStream, JoinHandle<()>)>,
+}
+
+/// A ground station serving CubeSats over TCP.
+///
+/// Connections are accepted on a background thread and each one is read on a
+/// thread of its own. Dropping the server shuts it down.
+pub struct GroundServer {
+    addr: SocketAddr,
+    shared: Arc<Mutex<Links>>,
+    stopping: Arc<AtomicBool>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl GroundServer {
+    /// Starts listening on a free port on localhost.
+    pub fn bind() -> io::Result<GroundServer> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Mutex::new(Links::default()));
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            let stopping = Arc::clone(&stopping);
+            thread::spawn(move || accept(listener, shared, stopping))
+        };
+
+        Ok(GroundServer {
+            addr,
+            shared,
+            stopping,
+            acceptor: Some(acceptor),
+        })
+    }
+
+    /// The address CubeSats connect to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Sends a message to its CubeSat, or queues it if the CubeSat is offline.
+    ///
+    /// A connection that fails to take the message is dropped, and the message
+    /// is queued for the next time the CubeSat connects.
+    pub fn send(&self, msg: Message) -> Delivery {
+        let link = {
+            let shared = self.shared.lock().unwrap();
+            shared
+                .links
+                .get(&msg.to)
+                .map(|link| (link.connection, Arc::clone(&link.stream)))
+        };
+        if let Some((connection, stream)) = link {
+            let mut stream = stream.lock().unwrap();
+            if write_frame(&mut *stream, &Frame::Message(msg.clone())).is_ok() {
+                return Delivery::Sent;
+            }
+            let mut shared = self.shared.lock().unwrap();
+            if shared.links.get(&msg.to).is_some_and(|link| link.connection == connection) {
+                shared.links.remove(&msg.to);
+            }
+        }
+        self.shared.lock().unwrap().mailbox.post(msg);
+        Delivery::Queued
+    }
+
+    /// Returns `true` if the CubeSat is registered on an open connection.
+    pub fn is_connected(&self, sat_id: u64) -> bool {
+        self.shared.lock().unwrap().links.contains_key(&sat_id)
+    }
+
+    /// The number of connections still open, registered or not.
+    pub fn connections(&self) -> usize {
+        self.shared.lock().unwrap().connections.len()
+    }
+
+    /// The number of messages queued for an offline CubeSat.
+    pub fn pending_for(&self, sat_id: u64) -> usize {
+        self.shared.lock().unwrap().mailbox.pending_for(sat_id)
+    }
+
+    /// Waits until the CubeSat's connection state is `connected`, for at most `timeout`.
+    ///
+    /// Connects and disconnects are noticed by the connection threads, so they
+    /// take effect a moment after the client acts.
+    pub fn wait_for(&self, sat_id: u64, connected: bool, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.is_connected(sat_id) != connected {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+        true
+    }
+
+    /// Stops accepting, closes every connection and joins all threads.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        let Some(acceptor) = self.acceptor.take() else {
+            return;
+        };
+        self.stopping.store(true, Ordering::SeqCst);
+        // Wake the acceptor, which is blocked in `accept`
+        let _ = TcpStream::connect(self.addr);
+        let _ = acceptor.join();
+
+        let connections = std::mem::take(&mut self.shared.lock().unwrap().connections);
+        for (stream, handle) in connections.into_values() {
+            let _ = stream.shutdown(Shutdown::Both);
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for GroundServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Accepts connections until the server stops, reading each on its own thread.
+fn accept(listener: TcpListener, shared: Arc<Mutex<Links>>, stopping: Arc<AtomicBool>) {
+    static NEXT_CONNECTION: AtomicU64 = AtomicU64::new(1);
+
+    for stream in listener.incoming() {
+        if stopping.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let Ok(handle_stream) = stream.try_clone() else {
+            continue;
+        };
+
+        let connection = NEXT_CONNECTION.fetch_add(1, Ordering::Relaxed);
+        // Held until the connection is listed, so its thread cannot end and
+        // unlist it first
+        let mut links = shared.lock().unwrap();
+        let handle = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || serve(connection, handle_stream, shared))
+        };
+        links.connections.insert(connection, (stream, handle));
+    }
+}
+
+/// Reads one connection until it closes, then forgets it.
+fn serve(connection: u64, stream: TcpStream, shared: Arc<Mutex<Links>>) {
+    let sat_id = listen(connection, stream, &shared);
+
+    let mut shared = shared.lock().unwrap();
+    if let Some(sat_id) = sat_id {
+        if shared.links.get(&sat_id).is_some_and(|link| link.connection == connection) {
+            shared.links.remove(&sat_id);
+        }
+    }
+    // This thread is about to finish, so its handle needs no joining
+    shared.connections.remove(&connection);
+}
+
+/// Registers the connection's CubeSat, then waits for it to hang up. Returns
+/// the CubeSat, if it registered.
+fn listen(connection: u64, stream: TcpStream, shared: &Mutex<Links>) -> Option<u64> {
+    let mut reader = FrameReader::new(stream);
+    reader.get_ref().set_read_timeout(Some(REGISTER_TIMEOUT)).ok()?;
+    let sat_id = match reader.read_frame() {
+        Ok(Some(Frame::Register { sat_id })) => sat_id,
+        // Anything else before registering, or nothing in time, is a protocol violation
+        _ => return None,
+    };
+    reader.get_ref().set_read_timeout(None).ok()?;
+    let stream = reader.get_ref().try_clone().ok()?;
+    register(connection, sat_id, stream, shared);
+
+    // CubeSats send nothing after registering; this ends when they disconnect
+    while let Ok(Some(_)) = reader.read_frame() {}
+    Some(sat_id)
+}
+
+/// Flushes the CubeSat's queued messages to `stream` and makes it the
+/// CubeSat's link.
+///
+/// The messages are written without holding the lock, and anything queued in
+/// the meantime is flushed too before the link goes live, so nothing is sent
+/// out of order. A newer connection for the same CubeSat replaces the old one.
+fn register(connection: u64, sat_id: u64, mut stream: TcpStream, shared: &Mutex<Links>) {
+    loop {
+        let queued: Vec<Message> = {
+            let mut shared = shared.lock().unwrap();
+            if shared.mailbox.pending_for(sat_id) == 0 {
+                let link = Link {
+                    connection,
+                    stream: Arc::new(Mutex::new(stream)),
+                };
+                if let Some(old) = shared.links.insert(sat_id, link) {
+                    if let Some((old_stream, _)) = shared.connections.get(&old.connection) {
+                        let _ = old_stream.shutdown(Shutdown::Both);
+                    }
+                }
+                return;
+            }
+            shared.mailbox.drain_for(sat_id).collect()
+        };
+
+        let mut queued = queued.into_iter();
+        for msg in queued.by_ref() {
+            if write_frame(&mut stream, &Frame::Message(msg.clone())).is_err() {
+                // Put the message back in front of the rest, and of anything newer
+                let mut shared = shared.lock().unwrap();
+                let newer: Vec<Message> = shared.mailbox.drain_for(sat_id).collect();
+                shared.mailbox.post(msg);
+                queued.chain(newer).for_each(|msg| shared.mailbox.post(msg));
+                return;
+            }
+        }
+    }
+}
+
+/// A CubeSat's connection to a [`GroundServer`].
+pub struct SatClient {
+    pub sat_id: u64,
+    reader: FrameReader<TcpStream>,
+}
+
+impl SatClient {
+    /// Connects and registers as the given CubeSat.
+    pub fn connect(addr: SocketAddr, sat_id: u64) -> io::Result<SatClient> {
+        let mut stream = TcpStream::connect(addr)?;
+        write_frame(&mut stream, &Frame::Register { sat_id })?;
+        Ok(SatClient {
+            sat_id,
+            reader: FrameReader::new(stream),
+        })
+    }
+
+    /// Waits for the next message; `None` once the server closes the connection.
+    pub fn recv(&mut self) -> io::Result<Option<Message>> {
+        match self.reader.read_frame()? {
+            Some(Frame::Message(msg)) => Ok(Some(msg)),
+            Some(other) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected frame from server: {:?}", other),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`recv`](Self::recv), but gives up with `TimedOut` or `WouldBlock` after `timeout`.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<Option<Message>> {
+        self.reader.get_ref().set_read_timeout(Some(timeout))?;
+        let msg = self.recv();
+        self.reader.get_ref().set_read_timeout(None)?;
+        msg
+    }
+
+    /// Closes the connection.
+    pub fn disconnect(self) {
+        let _ = self.reader.get_ref().shutdown(Shutdown::Both);
+    }
+}
+
+/// Runs the ground station as a TCP server on localhost with a client per CubeSat.
+///
+/// The last CubeSat drops out partway through and reconnects to collect the
+/// message queued for it while it was away.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    let sat_ids = fetch_sat_ids(sat_count);
+
+    writeln!(out, "=== Network Simulation ===")?;
+    let server = GroundServer::bind()?;
+    writeln!(out, "Ground station listening on localhost")?;
+
+    let mut clients = vec![];
+    for &sat_id in &sat_ids {
+        clients.push(SatClient::connect(server.local_addr(), sat_id)?);
+        server.wait_for(sat_id, true, TIMEOUT);
+        writeln!(out, "CubeSat {} connected", sat_id)?;
+    }
+
+    writeln!(out, "\n=== Uplink ===")?;
+    for (&sat_id, client) in sat_ids.iter().zip(&mut clients) {
+        server.send(Message {
+            to: sat_id,
+            content: format!("Hello over TCP, CubeSat {}!", sat_id),
+        });
+        match client.recv_timeout(TIMEOUT)? {
+            Some(msg) => writeln!(out, "CubeSat {} received: {}", sat_id, msg.content)?,
+            None => writeln!(out, "CubeSat {}: connection closed", sat_id)?,
+        }
+    }
+
+    writeln!(out, "\n=== Disconnect and Reconnect ===")?;
+    let client = clients.pop().expect("at least one CubeSat");
+    let sat_id = client.sat_id;
+    client.disconnect();
+    server.wait_for(sat_id, false, TIMEOUT);
+    writeln!(out, "CubeSat {} disconnected", sat_id)?;
+
+    let delivery = server.send(Message {
+        to: sat_id,
+        content: format!("Queued while CubeSat {} was away", sat_id),
+    });
+    let status = match delivery {
+        Delivery::Sent => "sent",
+        Delivery::Queued => "queued",
+    };
+    writeln!(out, "Message for CubeSat {} {} ({} pending)", sat_id, status, server.pending_for(sat_id))?;
+
+    let mut client = SatClient::connect(server.local_addr(), sat_id)?;
+    if let Some(msg) = client.recv_timeout(TIMEOUT)? {
+        writeln!(out, "CubeSat {} reconnected and received: {}", sat_id, msg.content)?;
+    }
+    clients.push(client);
+
+    server.shutdown();
+    let closed = clients
+        .iter_mut()
+        .map(|client| client.recv_timeout(TIMEOUT))
+        .filter(|result| matches!(result, Ok(None)))
+        .count();
+    writeln!(out, "Ground station shut down; {} of {} connections closed.", closed, clients.len())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(to: u64, content: &str) -> Frame {
+        Frame::Message(Message {
+            to,
+            content: content.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_frames_round_trip_byte_by_byte() {
+        let frames = [Frame::Register { sat_id: 7 }, message(7, "hello, CubeSat ✓"), message(1, "")];
+        let bytes: Vec<u8> = frames.iter().flat_map(|frame| frame.encode().unwrap()).collect();
+
+        let mut decoder = Decoder::new();
+        let mut decoded = vec![];
+        for byte in bytes {
+            decoder.push(&[byte]);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                decoded.push(frame);
+            }
+        }
+        assert_eq!(decoded, frames);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_frames() {
+        let mut decoder = Decoder::new();
+        decoder.push(&[0, 0, 0, 2, REGISTER, 0]);
+        assert_eq!(decoder.next_frame(), Err(FrameError::Truncated { tag: REGISTER, len: 2 }));
+
+        decoder.push(&[0, 0, 0, 1, 9]);
+        assert_eq!(decoder.next_frame(), Err(FrameError::UnknownTag(9)));
+
+        decoder.push(&u32::MAX.to_be_bytes());
+        assert_eq!(decoder.next_frame(), Err(FrameError::TooLarge(u32::MAX as usize)));
+
+        let huge = message(1, &"x".repeat(MAX_FRAME));
+        assert!(matches!(huge.encode(), Err(FrameError::TooLarge(_))));
+    }
+
+    #[test]
+    fn test_reader_reports_truncated_stream() {
+        let mut bytes = message(3, "cut short").encode().unwrap();
+        bytes.truncate(bytes.len() - 2);
+        let mut reader = FrameReader::new(bytes.as_slice());
+        let err = reader.read_frame().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut reader = FrameReader::new(&[][..]);
+        assert!(reader.read_frame().unwrap().is_none());
+    }
+}
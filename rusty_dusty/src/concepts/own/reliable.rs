@@ -0,0 +1,406 @@
+//! # Reliable Delivery
+//!
+//! [`Mailbox::deliver`](super::Mailbox::deliver) hands a message over and
+//! forgets it. A [`ReliableMailbox`] instead wraps each message in an
+//! [`Envelope`] with an id, a post time and a time-to-live, and keeps delivered
+//! messages in flight until the CubeSat acknowledges them:
+//!
+//! - a message that is not acked within the ack timeout goes back to the front
+//!   of its CubeSat's queue to be delivered again, and
+//! - a message still unacknowledged when its time-to-live runs out moves to the
+//!   dead-letter queue.
+//!
+//! Time comes from a [`Clock`], so tests can drive it with a [`ManualClock`].
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use super::{fetch_sat_ids, CubeSat, Message};
+
+/// A source of the current time, as an offset from some fixed start.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// Real time, measured from when the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Shares one clock between the mailbox and whoever advances it.
+impl<C: Clock> Clock for Rc<C> {
+    fn now(&self) -> Duration {
+        self.as_ref().now()
+    }
+}
+
+/// Identifies a message for acknowledgement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MessageId(pub u64);
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A message with the bookkeeping needed to track its delivery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    pub id: MessageId,
+    pub message: Message,
+    pub posted_at: Duration,
+    /// The message expires once the clock reaches this time.
+    pub expires_at: Duration,
+    /// How many times the message has been delivered.
+    pub attempts: u32,
+}
+
+/// A message that expired before it was acknowledged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    pub envelope: Envelope,
+    pub expired_at: Duration,
+}
+
+/// Why an acknowledgement was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckError {
+    /// The id was never delivered, or was already acked.
+    NotInFlight(MessageId),
+    /// The message expired and went to the dead-letter queue.
+    Expired(MessageId),
+}
+
+impl fmt::Display for AckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AckError::NotInFlight(id) => write!(f, "message {} is not awaiting an ack", id),
+            AckError::Expired(id) => write!(f, "message {} expired before it was acked", id),
+        }
+    }
+}
+
+/// A delivered message waiting for its ack.
+#[derive(Debug)]
+struct InFlight {
+    envelope: Envelope,
+    delivered_at: Duration,
+}
+
+/// A mailbox that redelivers unacknowledged messages and expires stale ones.
+#[derive(Debug)]
+pub struct ReliableMailbox<C: Clock> {
+    clock: C,
+    ack_timeout: Duration,
+    next_id: u64,
+    queues: BTreeMap<u64, VecDeque<Envelope>>,
+    in_flight: HashMap<MessageId, InFlight>,
+    dead_letters: Vec<DeadLetter>,
+}
+
+impl<C: Clock> ReliableMailbox<C> {
+    /// Creates a mailbox that redelivers messages not acked within `ack_timeout`.
+    pub fn new(clock: C, ack_timeout: Duration) -> Self {
+        ReliableMailbox {
+            clock,
+            ack_timeout,
+            next_id: 1,
+            queues: BTreeMap::new(),
+            in_flight: HashMap::new(),
+            dead_letters: vec![],
+        }
+    }
+
+    /// Queues a message that expires `ttl` from now, returning its id.
+    pub fn post(&mut self, message: Message, ttl: Duration) -> MessageId {
+        let id = MessageId(self.next_id);
+        self.next_id += 1;
+
+        let now = self.clock.now();
+        let envelope = Envelope {
+            id,
+            posted_at: now,
+            expires_at: now + ttl,
+            attempts: 0,
+            message,
+        };
+        self.queues.entry(envelope.message.to).or_default().push_back(envelope);
+        id
+    }
+
+    /// Delivers the oldest live message for a CubeSat and waits for its ack.
+    ///
+    /// The returned envelope is a copy; the mailbox keeps the message until
+    /// [`ack`](Self::ack) is called with its id.
+    pub fn deliver(&mut self, recipient: &CubeSat) -> Option<Envelope> {
+        self.tick();
+        let queue = self.queues.get_mut(&recipient.id)?;
+        let mut envelope = queue.pop_front()?;
+        if queue.is_empty() {
+            self.queues.remove(&recipient.id);
+        }
+
+        envelope.attempts += 1;
+        self.in_flight.insert(
+            envelope.id,
+            InFlight {
+                envelope: envelope.clone(),
+                delivered_at: self.clock.now(),
+            },
+        );
+        Some(envelope)
+    }
+
+    /// Confirms that a delivered message was processed.
+    pub fn ack(&mut self, id: MessageId) -> Result<(), AckError> {
+        self.tick();
+        if self.in_flight.remove(&id).is_some() {
+            return Ok(());
+        }
+        if self.dead_letters.iter().any(|dead| dead.envelope.id == id) {
+            return Err(AckError::Expired(id));
+        }
+        Err(AckError::NotInFlight(id))
+    }
+
+    /// Expires stale messages and requeues deliveries whose ack timed out.
+    ///
+    /// Called by `deliver` and `ack`, so only needed to observe the effect of
+    /// time passing without either.
+    pub fn tick(&mut self) {
+        let now = self.clock.now();
+
+        // Requeue in id order, so older messages end up ahead of newer ones
+        let mut overdue: Vec<MessageId> = self
+            .in_flight
+            .iter()
+            .filter(|(_, flight)| now >= flight.delivered_at + self.ack_timeout)
+            .map(|(id, _)| *id)
+            .collect();
+        overdue.sort_unstable_by(|a, b| b.cmp(a));
+        for id in overdue {
+            let flight = self.in_flight.remove(&id).unwrap();
+            let queue = self.queues.entry(flight.envelope.message.to).or_default();
+            queue.push_front(flight.envelope);
+        }
+
+        let mut expired = vec![];
+        self.in_flight.retain(|_, flight| {
+            let live = now < flight.envelope.expires_at;
+            if !live {
+                expired.push(flight.envelope.clone());
+            }
+            live
+        });
+        for queue in self.queues.values_mut() {
+            queue.retain(|envelope| {
+                let live = now < envelope.expires_at;
+                if !live {
+                    expired.push(envelope.clone());
+                }
+                live
+            });
+        }
+        self.queues.retain(|_, queue| !queue.is_empty());
+
+        expired.sort_by_key(|envelope| envelope.id);
+        self.dead_letters.extend(expired.into_iter().map(|envelope| DeadLetter {
+            envelope,
+            expired_at: now,
+        }));
+    }
+
+    /// Messages waiting for a CubeSat, not counting ones in flight.
+    pub fn pending_for(&self, sat_id: u64) -> usize {
+        self.queues.get(&sat_id).map_or(0, VecDeque::len)
+    }
+
+    /// The number of delivered messages awaiting an ack.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Messages that expired, in the order they expired.
+    pub fn dead_letters(&self) -> &[DeadLetter] {
+        &self.dead_letters
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+/// Delivers messages that must be acknowledged, on a simulated clock.
+///
+/// CubeSats with a flat battery never ack, so their message is retried at
+/// every contact pass until it expires into the dead-letter queue.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+    const TTL: Duration = Duration::from_secs(25);
+    let sats: Vec<CubeSat> = fetch_sat_ids(sat_count).into_iter().map(|id| CubeSat { id }).collect();
+
+    let clock = Rc::new(ManualClock::new());
+    let mut mailbox = ReliableMailbox::new(Rc::clone(&clock), ACK_TIMEOUT);
+
+    writeln!(out, "=== Reliable Delivery ===")?;
+    writeln!(out, "Ack timeout {:?}, messages expire after {:?}", ACK_TIMEOUT, TTL)?;
+    for sat in &sats {
+        let id = mailbox.post(
+            Message {
+                to: sat.id,
+                content: format!("Telemetry request for CubeSat {}", sat.id),
+            },
+            TTL,
+        );
+        writeln!(out, "Posted {} for CubeSat {}", id, sat.id)?;
+    }
+
+    for pass in 0..4 {
+        writeln!(out, "\n--- Contact pass at t={}s ---", clock.now().as_secs())?;
+        for sat in &sats {
+            let Some(envelope) = mailbox.deliver(sat) else {
+                continue;
+            };
+            write!(out, "CubeSat {} got {} (attempt {})", sat.id, envelope.id, envelope.attempts)?;
+            if sat.battery() > 1 {
+                mailbox.ack(envelope.id).expect("acked while in flight");
+                writeln!(out, ", acked")?;
+            } else {
+                writeln!(out, ", no ack: battery flat")?;
+            }
+        }
+        if pass < 3 {
+            clock.advance(ACK_TIMEOUT);
+        }
+    }
+
+    writeln!(out, "\n=== Dead Letters ===")?;
+    for dead in mailbox.dead_letters() {
+        writeln!(
+            out,
+            "{} for CubeSat {} expired at t={}s after {} attempts",
+            dead.envelope.id,
+            dead.envelope.message.to,
+            dead.expired_at.as_secs(),
+            dead.envelope.attempts
+        )?;
+    }
+    writeln!(out, "{} dead, {} still in flight", mailbox.dead_letters().len(), mailbox.in_flight())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAT: CubeSat = CubeSat { id: 1 };
+
+    fn mailbox() -> (Rc<ManualClock>, ReliableMailbox<Rc<ManualClock>>) {
+        let clock = Rc::new(ManualClock::new());
+        let mailbox = ReliableMailbox::new(Rc::clone(&clock), Duration::from_secs(10));
+        (clock, mailbox)
+    }
+
+    fn message(content: &str) -> Message {
+        Message {
+            to: SAT.id,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_acked_message_is_gone() {
+        let (clock, mut mailbox) = mailbox();
+        let id = mailbox.post(message("ping"), Duration::from_secs(60));
+        let envelope = mailbox.deliver(&SAT).unwrap();
+        assert_eq!((envelope.id, envelope.attempts), (id, 1));
+
+        assert_eq!(mailbox.ack(id), Ok(()));
+        assert_eq!(mailbox.ack(id), Err(AckError::NotInFlight(id)));
+        clock.advance(Duration::from_secs(30));
+        assert!(mailbox.deliver(&SAT).is_none());
+    }
+
+    #[test]
+    fn test_unacked_message_is_redelivered_first() {
+        let (clock, mut mailbox) = mailbox();
+        let first = mailbox.post(message("first"), Duration::from_secs(60));
+        mailbox.post(message("second"), Duration::from_secs(60));
+
+        assert_eq!(mailbox.deliver(&SAT).unwrap().id, first);
+        clock.advance(Duration::from_secs(9));
+        assert_eq!(mailbox.deliver(&SAT).unwrap().message.content, "second");
+        assert!(mailbox.deliver(&SAT).is_none());
+
+        // The first delivery times out and is retried ahead of anything else
+        clock.advance(Duration::from_secs(1));
+        let retry = mailbox.deliver(&SAT).unwrap();
+        assert_eq!((retry.id, retry.attempts), (first, 2));
+        assert_eq!(mailbox.in_flight(), 2);
+    }
+
+    #[test]
+    fn test_expired_messages_go_to_dead_letters() {
+        let (clock, mut mailbox) = mailbox();
+        let delivered = mailbox.post(message("never acked"), Duration::from_secs(8));
+        let queued = mailbox.post(message("never delivered"), Duration::from_secs(5));
+        mailbox.post(message("still fresh"), Duration::from_secs(60));
+        assert_eq!(mailbox.deliver(&SAT).unwrap().id, delivered);
+
+        clock.advance(Duration::from_secs(8));
+        mailbox.tick();
+        let dead: Vec<MessageId> = mailbox.dead_letters().iter().map(|dead| dead.envelope.id).collect();
+        assert_eq!(dead, [delivered, queued]);
+        assert_eq!(mailbox.ack(delivered), Err(AckError::Expired(delivered)));
+        assert_eq!(mailbox.deliver(&SAT).unwrap().message.content, "still fresh");
+    }
+}
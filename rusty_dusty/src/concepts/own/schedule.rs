@@ -0,0 +1,409 @@
+//! # Contact Windows
+//!
+//! A real ground station can only reach a satellite while it passes overhead.
+//! A [`Schedule`] lists those passes as [`ContactWindow`]s, and a
+//! [`ScheduledStation`] refuses to connect outside them. Messages sent in the
+//! meantime wait in the station's mailbox, and each pass uplinks as many bytes
+//! of them as the window's bandwidth allows, chosen by [`plan_uplink`].
+//!
+//! Time comes from a [`Clock`], as in the [`reliable`](super::reliable) mailbox.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::reliable::{Clock, ManualClock};
+use super::{fetch_sat_ids, CubeSat, Mailbox, Message};
+
+/// A pass during which one CubeSat can be reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContactWindow {
+    pub sat_id: u64,
+    pub start: Duration,
+    /// The window closes at `end`, exclusive.
+    pub end: Duration,
+    /// How many bytes of message content can be uplinked during the pass.
+    pub bandwidth: usize,
+}
+
+impl ContactWindow {
+    pub fn contains(&self, at: Duration) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Every planned contact window, kept in start order.
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    windows: Vec<ContactWindow>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Schedule::default()
+    }
+
+    /// Adds a window, keeping the schedule sorted by start time.
+    pub fn add(&mut self, window: ContactWindow) {
+        let index = self.windows.partition_point(|other| other.start <= window.start);
+        self.windows.insert(index, window);
+    }
+
+    /// The index of the window open for a CubeSat at `at`, if any.
+    fn open_at(&self, sat_id: u64, at: Duration) -> Option<usize> {
+        self.windows
+            .iter()
+            .position(|window| window.sat_id == sat_id && window.contains(at))
+    }
+
+    /// The window open for a CubeSat at `at`, if any.
+    pub fn window_at(&self, sat_id: u64, at: Duration) -> Option<&ContactWindow> {
+        self.open_at(sat_id, at).map(|index| &self.windows[index])
+    }
+
+    /// The first window for a CubeSat that opens after `at`.
+    pub fn next_window(&self, sat_id: u64, at: Duration) -> Option<&ContactWindow> {
+        self.windows
+            .iter()
+            .find(|window| window.sat_id == sat_id && window.start > at)
+    }
+
+    pub fn windows(&self) -> &[ContactWindow] {
+        &self.windows
+    }
+}
+
+/// Why a CubeSat could not be reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactError {
+    /// No window is open now; holds when the next one opens, if one is scheduled.
+    OutOfWindow { sat_id: u64, next: Option<Duration> },
+}
+
+impl fmt::Display for ContactError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContactError::OutOfWindow { sat_id, next: Some(next) } => {
+                write!(f, "CubeSat {} is out of contact until t={}s", sat_id, next.as_secs())
+            }
+            ContactError::OutOfWindow { sat_id, next: None } => {
+                write!(f, "CubeSat {} has no contact windows left", sat_id)
+            }
+        }
+    }
+}
+
+/// What one uplink sent, and how much of the window's budget is left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uplink {
+    pub messages: Vec<Message>,
+    pub bytes: usize,
+    pub remaining: usize,
+}
+
+/// A ground station that can only reach CubeSats during their contact windows.
+#[derive(Debug)]
+pub struct ScheduledStation<C: Clock> {
+    clock: C,
+    schedule: Schedule,
+    mailbox: Mailbox,
+    /// Bytes already uplinked, by window index.
+    spent: HashMap<usize, usize>,
+}
+
+impl<C: Clock> ScheduledStation<C> {
+    pub fn new(clock: C, schedule: Schedule) -> Self {
+        ScheduledStation {
+            clock,
+            schedule,
+            mailbox: Mailbox::new(),
+            spent: HashMap::new(),
+        }
+    }
+
+    /// Connects to a CubeSat, failing unless one of its windows is open.
+    pub fn connect(&self, sat_id: u64) -> Result<CubeSat, ContactError> {
+        self.open_window(sat_id).map(|_| CubeSat { id: sat_id })
+    }
+
+    /// Queues a message until its recipient's next pass.
+    pub fn send(&mut self, msg: Message) {
+        self.mailbox.post(msg);
+    }
+
+    /// Uplinks queued messages to a CubeSat within the current window's budget.
+    ///
+    /// The messages that carry the most bytes without going over budget are
+    /// sent, oldest first; the rest stay queued, in order, for a later pass.
+    pub fn uplink(&mut self, sat: &CubeSat) -> Result<Uplink, ContactError> {
+        let index = self.open_window(sat.id)?;
+        let budget = self.schedule.windows[index].bandwidth;
+        let spent = self.spent.entry(index).or_default();
+
+        let queued: Vec<Message> = self.mailbox.drain_for(sat.id).collect();
+        let sizes: Vec<usize> = queued.iter().map(|msg| msg.content.len()).collect();
+        let mut planned = plan_uplink(&sizes, budget - *spent).into_iter().peekable();
+
+        let mut messages = vec![];
+        for (i, msg) in queued.into_iter().enumerate() {
+            if planned.next_if_eq(&i).is_some() {
+                messages.push(msg);
+            } else {
+                self.mailbox.post(msg);
+            }
+        }
+
+        let bytes = messages.iter().map(|msg| msg.content.len()).sum();
+        *spent += bytes;
+        Ok(Uplink {
+            messages,
+            bytes,
+            remaining: budget - *spent,
+        })
+    }
+
+    /// Messages waiting for a CubeSat's next pass.
+    pub fn pending_for(&self, sat_id: u64) -> usize {
+        self.mailbox.pending_for(sat_id)
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    fn open_window(&self, sat_id: u64) -> Result<usize, ContactError> {
+        let now = self.clock.now();
+        self.schedule.open_at(sat_id, now).ok_or(ContactError::OutOfWindow {
+            sat_id,
+            next: self.schedule.next_window(sat_id, now).map(|window| window.start),
+        })
+    }
+}
+
+/// The most messages times budget bytes [`plan_uplink`] searches for the best
+/// plan; it keeps a bit for each.
+pub const MAX_PLAN_CELLS: usize = 1 << 24;
+
+/// Picks which messages to uplink, as indices into `sizes` in ascending order.
+///
+/// This is the 0/1 knapsack problem with each message's value equal to its
+/// size: the chosen sizes sum to as much as possible without exceeding
+/// `budget`. Ties go to the plan that sends older messages. Takes
+/// `O(sizes.len() * budget)` time and bits of memory, so once the number of
+/// messages times `budget` goes over [`MAX_PLAN_CELLS`] it stops searching and
+/// takes messages oldest first, skipping any that no longer fit.
+pub fn plan_uplink(sizes: &[usize], budget: usize) -> Vec<usize> {
+    if sizes.iter().sum::<usize>() <= budget {
+        return (0..sizes.len()).collect();
+    }
+    if sizes.len().saturating_mul(budget.saturating_add(1)) > MAX_PLAN_CELLS {
+        return plan_oldest_first(sizes, budget);
+    }
+
+    // best[b]: the most bytes that fit in `b` using only the messages after
+    // the current one; take[i * width + b]: whether an optimal plan for `b`
+    // using messages i.. sends message i
+    let width = budget + 1;
+    let mut best = vec![0; width];
+    let mut take = vec![0u64; (sizes.len() * width).div_ceil(64)];
+    for (i, &size) in sizes.iter().enumerate().rev() {
+        // Downwards, so `best[b - size]` still excludes message i
+        for b in (size..=budget).rev() {
+            let with = size + best[b - size];
+            if with >= best[b] {
+                best[b] = with;
+                let bit = i * width + b;
+                take[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+    }
+
+    // Walk forward, taking each message whenever an optimal plan includes it
+    let mut plan = vec![];
+    let mut b = budget;
+    for (i, &size) in sizes.iter().enumerate() {
+        let bit = i * width + b;
+        if take[bit / 64] & (1 << (bit % 64)) != 0 {
+            plan.push(i);
+            b -= size;
+        }
+    }
+    plan
+}
+
+/// Takes messages oldest first, skipping any that no longer fit.
+fn plan_oldest_first(sizes: &[usize], mut budget: usize) -> Vec<usize> {
+    let mut plan = vec![];
+    for (i, &size) in sizes.iter().enumerate() {
+        if size <= budget {
+            plan.push(i);
+            budget -= size;
+        }
+    }
+    plan
+}
+
+/// Uplinks messages only during each CubeSat's contact windows, on a simulated clock.
+///
+/// Each CubeSat passes over twice, a minute apart, with room for 56 bytes per
+/// pass. The first pass sends the two shorter commands, which fill more of the
+/// budget than the oldest one would alone; it waits for the next pass.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    const STEP: Duration = Duration::from_secs(10);
+    const BANDWIDTH: usize = 56;
+    let sat_ids = fetch_sat_ids(sat_count);
+
+    let mut schedule = Schedule::new();
+    for (i, &sat_id) in sat_ids.iter().enumerate() {
+        for pass in 0..2 {
+            let start = STEP * (i as u32 + 1) + Duration::from_secs(60) * pass;
+            schedule.add(ContactWindow {
+                sat_id,
+                start,
+                end: start + STEP,
+                bandwidth: BANDWIDTH,
+            });
+        }
+    }
+    let end = schedule.windows().last().map_or(Duration::ZERO, |window| window.end);
+
+    let clock = Rc::new(ManualClock::new());
+    let mut station = ScheduledStation::new(Rc::clone(&clock), schedule);
+
+    writeln!(out, "=== Contact Windows ===")?;
+    for window in station.schedule().windows() {
+        writeln!(
+            out,
+            "CubeSat {}: t={}s..{}s, {} bytes",
+            window.sat_id,
+            window.start.as_secs(),
+            window.end.as_secs(),
+            window.bandwidth
+        )?;
+    }
+
+    for &sat_id in &sat_ids {
+        for content in ["Rotate solar panels", "Downlink images", "Run diagnostics"] {
+            station.send(Message {
+                to: sat_id,
+                content: format!("{} (CubeSat {})", content, sat_id),
+            });
+        }
+    }
+    if let Err(err) = station.connect(sat_ids[0]) {
+        writeln!(out, "\nConnecting at t=0s failed: {}", err)?;
+    }
+
+    while clock.now() < end {
+        for &sat_id in &sat_ids {
+            let Ok(sat) = station.connect(sat_id) else {
+                continue;
+            };
+            let uplink = station.uplink(&sat).map_err(|err| io::Error::other(err.to_string()))?;
+            writeln!(out, "\n--- Pass of CubeSat {} at t={}s ---", sat_id, clock.now().as_secs())?;
+            for msg in &uplink.messages {
+                writeln!(out, "Uplinked {} bytes: {}", msg.content.len(), msg.content)?;
+            }
+            writeln!(
+                out,
+                "{} of {} bytes used, {} queued for the next pass",
+                uplink.bytes,
+                BANDWIDTH,
+                station.pending_for(sat_id)
+            )?;
+        }
+        clock.advance(STEP);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concepts::own::reliable::ManualClock;
+    use std::rc::Rc;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn message(content: &str) -> Message {
+        Message {
+            to: 1,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_plan_uplink_maximizes_bytes() {
+        assert_eq!(plan_uplink(&[6, 5, 5], 10), [1, 2]);
+        assert_eq!(plan_uplink(&[4, 4, 4], 8), [0, 1]);
+        assert_eq!(plan_uplink(&[11, 3], 10), [1]);
+        assert_eq!(plan_uplink(&[], 10), Vec::<usize>::new());
+        assert_eq!(plan_uplink(&[0, 1], 0), [0]);
+        assert_eq!(plan_uplink(&[2, 7, 3, 3, 1], 9), [0, 1]);
+    }
+
+    #[test]
+    fn test_plan_uplink_stays_within_its_limit() {
+        // Everything fits, so no table is needed however large the budget
+        assert_eq!(plan_uplink(&[5, 5], usize::MAX), [0, 1]);
+
+        // Too big to search, so the oldest message goes first even though the
+        // other two would fill the budget exactly
+        let half = MAX_PLAN_CELLS / 2;
+        assert_eq!(plan_uplink(&[half + 1, half, half], MAX_PLAN_CELLS), [0]);
+    }
+
+    #[test]
+    fn test_connect_only_inside_windows() {
+        let clock = Rc::new(ManualClock::new());
+        let mut schedule = Schedule::new();
+        schedule.add(ContactWindow { sat_id: 1, start: secs(20), end: secs(30), bandwidth: 10 });
+        schedule.add(ContactWindow { sat_id: 1, start: secs(5), end: secs(10), bandwidth: 10 });
+        let station = ScheduledStation::new(Rc::clone(&clock), schedule);
+
+        assert_eq!(station.connect(1).unwrap_err(), ContactError::OutOfWindow { sat_id: 1, next: Some(secs(5)) });
+        clock.advance(secs(5));
+        assert!(station.connect(1).is_ok());
+        clock.advance(secs(5));
+        assert_eq!(station.connect(1).unwrap_err().to_string(), "CubeSat 1 is out of contact until t=20s");
+        clock.advance(secs(20));
+        assert_eq!(station.connect(1).unwrap_err().to_string(), "CubeSat 1 has no contact windows left");
+    }
+
+    #[test]
+    fn test_uplink_spends_the_window_budget() {
+        let clock = Rc::new(ManualClock::new());
+        let mut schedule = Schedule::new();
+        schedule.add(ContactWindow { sat_id: 1, start: secs(0), end: secs(10), bandwidth: 10 });
+        schedule.add(ContactWindow { sat_id: 1, start: secs(20), end: secs(30), bandwidth: 10 });
+        let mut station = ScheduledStation::new(Rc::clone(&clock), schedule);
+        let sat = station.connect(1).unwrap();
+
+        for content in ["aaaaaa", "bbbbb", "ccccc", "dd"] {
+            station.send(message(content));
+        }
+        let uplink = station.uplink(&sat).unwrap();
+        let sent: Vec<&str> = uplink.messages.iter().map(|msg| msg.content.as_str()).collect();
+        assert_eq!(sent, ["bbbbb", "ccccc"]);
+        assert_eq!((uplink.bytes, uplink.remaining), (10, 0));
+
+        // The window's budget is spent, so later messages wait for the next pass
+        station.send(message("e"));
+        assert!(station.uplink(&sat).unwrap().messages.is_empty());
+        clock.advance(secs(10));
+        assert!(station.uplink(&sat).is_err());
+        assert_eq!(station.pending_for(1), 3);
+
+        clock.advance(secs(10));
+        let sent: Vec<String> = station.uplink(&sat).unwrap().messages.into_iter().map(|msg| msg.content).collect();
+        assert_eq!(sent, ["aaaaaa", "dd", "e"]);
+    }
+}
@@ -0,0 +1,160 @@
+//! # Threaded Simulation
+//!
+//! Runs one contact pass with every CubeSat on its own thread. Messages are
+//! moved to the satellites over `mpsc` channels, and their contents come back
+//! in the [`Ack`]s sent to the ground.
+
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use super::{fetch_sat_ids, CubeSat, GroundStation, Mailbox, Message};
+
+/// The receipt a CubeSat sends back to the ground for each message it receives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ack {
+    pub sat_id: u64,
+    pub content: String,
+}
+
+/// How the messages of a threaded simulation ended up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulationReport {
+    /// Acknowledgements, grouped by CubeSat in the order each one received them.
+    pub acks: Vec<Ack>,
+    /// Messages still waiting in the mailbox for the next contact window.
+    pub pending: usize,
+    /// Messages uplinked to a CubeSat that never acknowledged them.
+    pub lost: usize,
+}
+
+impl SimulationReport {
+    pub fn delivered(&self) -> usize {
+        self.acks.len()
+    }
+}
+
+/// Messages queued for each CubeSat in the threaded simulation.
+const MESSAGES_PER_SAT: usize = 3;
+
+/// Messages the ground station can uplink to each CubeSat in one pass.
+const CONTACT_WINDOW: usize = 2;
+
+impl CubeSat {
+    /// How many messages the satellite handles before its battery runs flat.
+    ///
+    /// Every third satellite is low on power, so the simulation has losses.
+    pub(super) fn battery(&self) -> usize {
+        if self.id.is_multiple_of(3) {
+            1
+        } else {
+            usize::MAX
+        }
+    }
+
+    /// The satellite's own thread: acknowledges each message from the uplink
+    /// until the ground station closes it or the battery runs flat.
+    ///
+    /// Each `Message` is moved across the channel into this thread, and its
+    /// content moves on into the `Ack` sent back.
+    fn listen(self, uplink: Receiver<Message>, downlink: Sender<Ack>) {
+        for msg in uplink.iter().take(self.battery()) {
+            let ack = Ack {
+                sat_id: self.id,
+                content: msg.content,
+            };
+            if downlink.send(ack).is_err() {
+                break;
+            }
+        }
+        // Returning drops `uplink`, so later sends to this satellite fail
+    }
+}
+
+/// Runs one contact pass with every CubeSat on its own thread.
+///
+/// The ground station queues [`MESSAGES_PER_SAT`] messages per satellite in its
+/// mailbox and uplinks up to [`CONTACT_WINDOW`] of them to each satellite over an
+/// `mpsc` channel. Satellites acknowledge over a shared channel. Closing the
+/// uplinks shuts the satellites down, and every thread is joined before the
+/// report is made.
+pub fn simulate_threaded(sat_ids: &[u64]) -> SimulationReport {
+    let base = GroundStation;
+    let mut mailbox = Mailbox::new();
+    for round in 1..=MESSAGES_PER_SAT {
+        for &sat_id in sat_ids {
+            let msg = Message {
+                to: sat_id,
+                content: format!("Command {} for CubeSat {}", round, sat_id),
+            };
+            base.send_with_reference(&mut mailbox, msg);
+        }
+    }
+
+    let (downlink, acks) = mpsc::channel();
+    let mut satellites = vec![];
+    for &sat_id in sat_ids {
+        let sat = base.connect(sat_id);
+        let (uplink, receiver) = mpsc::channel();
+        let downlink = downlink.clone();
+        let handle = thread::spawn(move || sat.listen(receiver, downlink));
+        satellites.push((sat, uplink, handle));
+    }
+    // Only the satellites hold senders now, so `acks` ends when they all stop
+    drop(downlink);
+
+    let mut uplinked = 0;
+    for (sat, uplink, _) in &satellites {
+        for _ in 0..CONTACT_WINDOW {
+            let Some(msg) = sat.recv(&mut mailbox) else {
+                break;
+            };
+            uplinked += 1;
+            // A satellite with a flat battery has hung up; the message is lost
+            let _ = uplink.send(msg);
+        }
+    }
+
+    let mut handles = vec![];
+    for (_, uplink, handle) in satellites {
+        drop(uplink);
+        handles.push(handle);
+    }
+    let mut acks: Vec<Ack> = acks.iter().collect();
+    for handle in handles {
+        handle.join().expect("CubeSat thread panicked");
+    }
+
+    acks.sort_by_key(|ack| ack.sat_id);
+    SimulationReport {
+        lost: uplinked - acks.len(),
+        pending: mailbox.len(),
+        acks,
+    }
+}
+
+/// Prints a threaded simulation run.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    let sat_ids = fetch_sat_ids(sat_count);
+
+    writeln!(out, "=== Threaded Simulation ===")?;
+    writeln!(
+        out,
+        "{} CubeSats on their own threads, {} messages each, contact window of {}",
+        sat_ids.len(),
+        MESSAGES_PER_SAT,
+        CONTACT_WINDOW
+    )?;
+
+    let report = simulate_threaded(&sat_ids);
+    for ack in &report.acks {
+        writeln!(out, "CubeSat {} acknowledged: {}", ack.sat_id, ack.content)?;
+    }
+
+    writeln!(out, "\n=== Report ===")?;
+    writeln!(out, "Delivered: {}", report.delivered())?;
+    writeln!(out, "Pending:   {}", report.pending)?;
+    writeln!(out, "Lost:      {}", report.lost)?;
+    writeln!(out, "All CubeSat threads shut down cleanly.")?;
+    Ok(())
+}
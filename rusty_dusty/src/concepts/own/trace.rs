@@ -0,0 +1,60 @@
+//! # Ownership Timeline
+//!
+//! Replays the `own` demo's first three methods with the mailbox and messages
+//! wrapped in [`Tracked`], so every creation, borrow, move and drop shows up in
+//! a timeline.
+
+use std::io::{self, Write};
+
+use super::{fetch_sat_ids, GroundStation, Mailbox, Message};
+use crate::trace::{self, Tracked};
+
+/// Replays the first three methods with the mailbox and messages tracked,
+/// printing a timeline of every creation, borrow, move and drop.
+pub fn main(sat_count: u64, out: &mut dyn Write) -> io::Result<()> {
+    let base = GroundStation;
+    let sat_ids = fetch_sat_ids(sat_count);
+    let message = |sat_id: u64, kind: &str| {
+        Tracked::new(Message {
+            to: sat_id,
+            content: format!("{} msg for CubeSat {}!", kind, sat_id),
+        })
+    };
+    trace::reset();
+
+    writeln!(out, "=== Ownership Timeline ===")?;
+    {
+        writeln!(out, "\n--- Method 1: Using References ---")?;
+        let mut mailbox = Tracked::new(Mailbox::new());
+        for &sat_id in &sat_ids {
+            let msg = message(sat_id, "Borrowed");
+            base.send_with_reference(&mut mailbox.borrow_mut(), msg.move_into("send_with_reference"));
+        }
+        trace::write_timeline(out)?;
+
+        writeln!(out, "\n--- Method 2: Cloning ---")?;
+        let msg = message(sat_ids[0], "Cloned");
+        let copy = base.send_with_clone(mailbox.move_into("send_with_clone"), msg.move_into("send_with_clone"));
+        let mailbox = Tracked::returned_from("send_with_clone", copy);
+        trace::write_timeline(out)?;
+
+        writeln!(out, "\n--- Method 3: Refactoring to Return Ownership ---")?;
+        let msg = message(sat_ids[0], "Refactored");
+        let mailbox = mailbox.pass("send_with_refactor", |mailbox| {
+            base.send_with_refactor(mailbox, msg.move_into("send_with_refactor"))
+        });
+        trace::write_timeline(out)?;
+
+        writeln!(out, "\n--- Receiving ---")?;
+        let sat = base.connect(sat_ids[0]);
+        let mut mailbox = mailbox;
+        while let Some(msg) = sat.recv(&mut mailbox.borrow_mut()) {
+            let msg = Tracked::returned_from("recv", msg);
+            writeln!(out, "CubeSat {} received: {}", sat.id, msg.borrow().content)?;
+        }
+        trace::write_timeline(out)?;
+
+        writeln!(out, "\n--- End of Scope ---")?;
+    }
+    trace::write_timeline(out)
+}
@@ -1,4 +1,8 @@
 use std::cmp::Ordering;
+use std::io::{self, Write};
+
+use crate::demo::{Category, Entry};
+use crate::trace::{self, Tracked};
 
 /// A trait representing general animal behavior.
 trait Animal {
@@ -77,16 +81,20 @@ impl Flyable for Bird {
 }
 
 /// A generic activity function for all walkable animals.
-fn perform_walk<T: Animal + Walkable>(animal: &T) {
-    println!("{}", animal.description());
-    println!("{}", animal.walk());
+fn perform_walk<T: Animal + Walkable>(animal: &T, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", animal.description())?;
+    writeln!(out, "{}", animal.walk())?;
+
+    Ok(())
 }
 
 /// Accept any animal (dynamic dispatch via trait objects).
-fn print_animal_sounds(animals: Vec<Box<dyn Animal>>) {
+fn print_animal_sounds(animals: Vec<Box<dyn Animal>>, out: &mut dyn Write) -> io::Result<()> {
     for animal in animals {
-        println!("{} says: {}", animal.name(), animal.speak());
+        writeln!(out, "{} says: {}", animal.name(), animal.speak())?;
     }
+
+    Ok(())
 }
 
 /// Trait with associated type
@@ -114,15 +122,15 @@ impl<T> Container for ToyBox<T> {
 }
 
 /// Trait for comparing items (generic)
-trait Comparable<T> {
+pub trait Comparable<T> {
     fn compare(&self, other: &T) -> Ordering;
 }
 
 /// Temperature types with `From` and custom trait.
 #[derive(Debug)]
-struct Celsius(f64);
+pub struct Celsius(pub f64);
 #[derive(Debug)]
-struct Fahrenheit(f64);
+pub struct Fahrenheit(pub f64);
 
 impl From<Fahrenheit> for Celsius {
     fn from(f: Fahrenheit) -> Self {
@@ -152,8 +160,8 @@ impl Default for Dog {
 }
 
 /// Demo function to run everything
-pub fn trait_demo() {
-    println!("--- Animal Traits Demo ---");
+pub fn trait_demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "--- Animal Traits Demo ---")?;
 
     let dog = Dog {
         name: "Buddy".into(),
@@ -162,38 +170,64 @@ pub fn trait_demo() {
         name: "Tweety".into(),
     };
 
-    perform_walk(&dog);
-    perform_walk(&bird);
-    println!("{}", bird.fly());
+    perform_walk(&dog, out)?;
+    perform_walk(&bird, out)?;
+    writeln!(out, "{}", bird.fly())?;
 
     let animals: Vec<Box<dyn Animal>> = vec![
         Box::new(dog.clone()),
         Box::new(bird),
         Box::new(Dog::default()),
     ];
-    print_animal_sounds(animals);
+    print_animal_sounds(animals, out)?;
 
-    println!("\n--- Container Trait ---");
+    writeln!(out, "\n--- Container Trait ---")?;
     let mut toy_box = ToyBox { toy: None };
     toy_box.insert("Rubber Bone");
     if let Some(toy) = toy_box.retrieve() {
-        println!("The toy in the box is: {}", toy);
+        writeln!(out, "The toy in the box is: {}", toy)?;
     }
 
-    println!("\n--- Temperature Conversion ---");
+    writeln!(out, "\n--- Temperature Conversion ---")?;
     let f = Fahrenheit(100.0);
     let c: Celsius = f.into();
-    println!("100°F in Celsius is {:.2}°C", c.0);
+    writeln!(out, "100°F in Celsius is {:.2}°C", c.0)?;
 
     let c2 = Celsius(37.0);
     match c.compare(&c2) {
-        Ordering::Less => println!("Cooler than normal."),
-        Ordering::Equal => println!("Just right."),
-        Ordering::Greater => println!("Hot!"),
-    }
+        Ordering::Less => writeln!(out, "Cooler than normal.")?,
+        Ordering::Equal => writeln!(out, "Just right.")?,
+        Ordering::Greater => writeln!(out, "Hot!")?,
+    }
+
+    writeln!(out, "\n--- Ownership Timeline ---")?;
+    trace::reset();
+    {
+        let rex = Tracked::new(Dog { name: "Rex".into() });
+        let twin = rex.clone();
+        writeln!(out, "{}", rex.borrow().walk())?;
+        let mut kennel = ToyBox { toy: None };
+        kennel.insert(twin.move_into("the kennel"));
+        if let Some(dog) = kennel.retrieve() {
+            writeln!(out, "{} is in the kennel", dog.name)?;
+        }
+    } // `kennel` and `rex` are dropped here, in reverse order of declaration
+    trace::write_timeline(out)?;
 
-    println!("\n--- Drop Trait Demonstration ---");
+    writeln!(out, "\n--- Drop Trait Demonstration ---")?;
     let _temp_dog = Dog {
         name: "Shadow".into(),
     }; // Will trigger `drop()` at the end of scope
+
+    Ok(())
 }
+
+/// Registry entry for the `traits` demo.
+pub const DEMO: Entry = Entry {
+    name: "traits",
+    category: Category::Concepts,
+    summary: "Traits, default methods, trait objects, associated types, `From`, `Drop` and `Default`.",
+    prerequisites: &["generics"],
+    run: |_, out| trait_demo(out),
+    ..Entry::DEFAULTS
+};
@@ -1,5 +1,8 @@
+use std::io::{self, Write};
 use std::{f32::consts::PI as PI32, f64::consts::PI as PI64};
 
+use crate::demo::{Category, Entry};
+
 /// Demonstrates usage of tuples in Rust.
 ///
 /// This function covers:
@@ -10,66 +13,66 @@ use std::{f32::consts::PI as PI32, f64::consts::PI as PI64};
 /// - Copying and cloning tuples
 /// - Using tuples as return values and function parameters
 /// - Simple tuple processing
-pub fn main() {
-    println!("Tuples");
+pub fn main(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Tuples")?;
     
     // simple tuple with type inferences.
     let tuple = (1, "hello", PI32);
-    println!("Simple tuple: {:?}", tuple);
+    writeln!(out, "Simple tuple: {:?}", tuple)?;
 
     // xxplicit type declaration.
     let tuple_explicit: (i32, &str, f64) = (1, "hello", PI64);
-    println!("Explicitly typed tuple: {:?}", tuple_explicit);
+    writeln!(out, "Explicitly typed tuple: {:?}", tuple_explicit)?;
 
     // unit tuple.
     let unit = ();
-    println!("Unit tuple: {:?}", unit);
+    writeln!(out, "Unit tuple: {:?}", unit)?;
 
     // nested tuples.
     let nested = (1, ("hello", PI64), true);
-    println!("Nested tuple: {:?}", nested);
+    writeln!(out, "Nested tuple: {:?}", nested)?;
 
     // accessing tuple elements.
-    println!("\nAccessing Tuple Elements:");
+    writeln!(out, "\nAccessing Tuple Elements:")?;
     
     // using index notation.
-    println!("First element: {}", tuple.0);
-    println!("Second element: {}", tuple.1);
-    println!("Third element: {}", tuple.2);
+    writeln!(out, "First element: {}", tuple.0)?;
+    writeln!(out, "Second element: {}", tuple.1)?;
+    writeln!(out, "Third element: {}", tuple.2)?;
 
     // destructuring.
     let (x, y, z) = tuple;
-    println!("Destructured values: x = {}, y = {}, z = {}", x, y, z);
+    writeln!(out, "Destructured values: x = {}, y = {}, z = {}", x, y, z)?;
 
     // mutable tuples.
-    println!("\nMutable Tuples:");
+    writeln!(out, "\nMutable Tuples:")?;
     
     let mut mutable_tuple = (1, String::from("night_fury"), PI64);
     mutable_tuple.1 = String::from("deepkaso");
-    println!("Modified tuple: {:?}", mutable_tuple);
+    writeln!(out, "Modified tuple: {:?}", mutable_tuple)?;
 
     // tuple methods and operations.
-    println!("\nTuple Methods and Operations:");
+    writeln!(out, "\nTuple Methods and Operations:")?;
 
     // copy and clone.
     let original = (1, 2, 3);    
     let mut copied = original;
     copied.0 = 2;
-    println!("Original tuple: {:?}", original);
-    println!("Copied tuple: {:?}", copied);
+    writeln!(out, "Original tuple: {:?}", original)?;
+    writeln!(out, "Copied tuple: {:?}", copied)?;
 
     // using tuples in functions.
-    println!("\nUsing Tuples in Functions:");
+    writeln!(out, "\nUsing Tuples in Functions:")?;
     
     fn return_tuple() -> (i32, &'static str) {
         (42, "answer")
     }
 
     let (number, text) = return_tuple();
-    println!("Returned tuple values: number = {}, text = {}", number, text);
+    writeln!(out, "Returned tuple values: number = {}, text = {}", number, text)?;
 
     // advanced tuple usage.
-    println!("\nAdvanced Tuple Usage:");
+    writeln!(out, "\nAdvanced Tuple Usage:")?;
 
     // tuple as function parameters
     fn process_tuple((x, y): (i32, i32)) -> i32 {
@@ -77,5 +80,17 @@ pub fn main() {
     }
 
     let sum = process_tuple((5, 3));
-    println!("Sum from tuple: {}\n", sum);
+    writeln!(out, "Sum from tuple: {}\n", sum)?;
+
+    Ok(())
 }
+
+/// Registry entry for the `tuples` demo.
+pub const DEMO: Entry = Entry {
+    name: "tuples",
+    category: Category::Concepts,
+    summary: "Tuple creation, indexing, destructuring and tuples in function signatures.",
+    prerequisites: &["data_types"],
+    run: |_, out| main(out),
+    ..Entry::DEFAULTS
+};
@@ -0,0 +1,382 @@
+//! # Demo Registry
+//!
+//! Every runnable demo implements the [`Demo`] trait, usually by declaring an
+//! [`Entry`], and registers itself in the `demos()` list of its category module
+//! (`concepts`, `collections` or `advanced`). The command line is driven
+//! entirely by this registry.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::exercise::Exercise;
+use crate::params::{Param, Params};
+use crate::{advanced, collections, concepts};
+
+/// The group a demo belongs to, mirroring the module layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Concepts,
+    Collections,
+    Advanced,
+}
+
+impl Category {
+    /// All categories in the order they are listed.
+    pub const ALL: [Category; 3] = [
+        Category::Concepts,
+        Category::Collections,
+        Category::Advanced,
+    ];
+
+    /// The lowercase name used on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Concepts => "concepts",
+            Category::Collections => "collections",
+            Category::Advanced => "advanced",
+        }
+    }
+
+    /// The demos registered under this category.
+    pub fn demos(&self) -> &'static [&'static dyn Demo] {
+        match self {
+            Category::Concepts => concepts::demos(),
+            Category::Collections => collections::demos(),
+            Category::Advanced => advanced::demos(),
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Category::ALL
+            .into_iter()
+            .find(|category| category.name() == s)
+            .ok_or_else(|| format!("Unknown category: {}", s))
+    }
+}
+
+/// A rule for hiding the parts of a demo's output that change from run to run,
+/// so the output can be compared against a recorded snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mask {
+    /// Replaces the rest of any line after this marker with `<masked>`.
+    After(&'static str),
+    /// Ignores the order of lines within each blank-line separated block.
+    Unordered,
+}
+
+/// A self-describing, runnable demo.
+pub trait Demo {
+    /// The name used to select the demo on the command line.
+    fn name(&self) -> &'static str;
+
+    /// The category the demo is listed under.
+    fn category(&self) -> Category;
+
+    /// A one-line description of what the demo covers.
+    fn summary(&self) -> &'static str;
+
+    /// Runs the demo with its default parameters, writing its output to `out`.
+    fn run(&self, out: &mut dyn Write) -> io::Result<()>;
+
+    /// Demos worth working through before this one, by name.
+    fn prerequisites(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Exercises for the learner on this demo's topic.
+    fn exercises(&self) -> &'static [Exercise] {
+        &[]
+    }
+
+    /// The parameters the demo accepts on the command line.
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    /// Runs the demo with the given parameters.
+    ///
+    /// Demos without parameters can rely on the default, which ignores them.
+    fn run_with(&self, params: &Params, out: &mut dyn Write) -> io::Result<()> {
+        let _ = params;
+        self.run(out)
+    }
+
+    /// Masks applied to the output before comparing it with a snapshot.
+    fn masks(&self) -> &'static [Mask] {
+        &[]
+    }
+}
+
+/// A demo described as data, which is how the built-in demos register.
+///
+/// Fields a demo does not need can be left to [`Entry::DEFAULTS`]:
+///
+/// ```ignore
+/// pub const DEMO: Entry = Entry {
+///     name: "tuples",
+///     category: Category::Concepts,
+///     summary: "Tuple creation, indexing and destructuring.",
+///     run: |_, out| main(out),
+///     ..Entry::DEFAULTS
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    pub name: &'static str,
+    pub category: Category,
+    pub summary: &'static str,
+    pub prerequisites: &'static [&'static str],
+    pub exercises: &'static [Exercise],
+    pub params: &'static [Param],
+    pub masks: &'static [Mask],
+    /// Runs the demo with values for `params`.
+    pub run: fn(&Params, &mut dyn Write) -> io::Result<()>,
+}
+
+impl Entry {
+    /// No prerequisites, exercises, parameters or masks, and nothing to run.
+    pub const DEFAULTS: Entry = Entry {
+        name: "",
+        category: Category::Concepts,
+        summary: "",
+        prerequisites: &[],
+        exercises: &[],
+        params: &[],
+        masks: &[],
+        run: |_, _| Ok(()),
+    };
+}
+
+impl Demo for Entry {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn category(&self) -> Category {
+        self.category
+    }
+
+    fn summary(&self) -> &'static str {
+        self.summary
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        (self.run)(&Params::defaults(self.params), out)
+    }
+
+    fn prerequisites(&self) -> &'static [&'static str] {
+        self.prerequisites
+    }
+
+    fn exercises(&self) -> &'static [Exercise] {
+        self.exercises
+    }
+
+    fn params(&self) -> &'static [Param] {
+        self.params
+    }
+
+    fn run_with(&self, params: &Params, out: &mut dyn Write) -> io::Result<()> {
+        (self.run)(params, out)
+    }
+
+    fn masks(&self) -> &'static [Mask] {
+        self.masks
+    }
+}
+
+/// Returns every registered demo, grouped by category.
+pub fn all() -> impl Iterator<Item = &'static dyn Demo> {
+    Category::ALL
+        .into_iter()
+        .flat_map(|category| category.demos().iter().copied())
+}
+
+/// Looks up a demo by its exact name.
+pub fn find(name: &str) -> Option<&'static dyn Demo> {
+    all().find(|demo| demo.name() == name)
+}
+
+/// Why a name given on the command line did not select any demo.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LookupError {
+    /// Nothing matched; `suggestions` holds the closest demo names, best first.
+    Unknown {
+        name: String,
+        suggestions: Vec<&'static str>,
+    },
+    /// The name is a prefix of more than one demo.
+    Ambiguous {
+        name: String,
+        candidates: Vec<&'static str>,
+    },
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LookupError::Unknown { name, suggestions } => {
+                write!(f, "Unknown demo: {}", name)?;
+                match suggestions.as_slice() {
+                    [] => Ok(()),
+                    [only] => write!(f, " (did you mean `{}`?)", only),
+                    several => write!(f, " (did you mean one of {}?)", quoted(several)),
+                }
+            }
+            LookupError::Ambiguous { name, candidates } => {
+                write!(f, "Ambiguous demo: {} matches {}", name, quoted(candidates))
+            }
+        }
+    }
+}
+
+fn quoted(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Resolves a command-line name to the demos it selects.
+///
+/// In order of preference, `pattern` may be an exact name, a glob such as
+/// `hash*` (`*` matches any run of characters, `?` a single one), or a prefix
+/// that only one demo starts with (`conc` for `concurrency`). Anything else is
+/// an error carrying the closest names by edit distance.
+pub fn resolve(pattern: &str) -> Result<Vec<&'static dyn Demo>, LookupError> {
+    if let Some(demo) = find(pattern) {
+        return Ok(vec![demo]);
+    }
+
+    let matches: Vec<&'static dyn Demo> = if pattern.contains(['*', '?']) {
+        all().filter(|demo| glob_match(pattern, demo.name())).collect()
+    } else {
+        all().filter(|demo| demo.name().starts_with(pattern)).collect()
+    };
+
+    match matches.len() {
+        0 => Err(LookupError::Unknown {
+            name: pattern.to_string(),
+            suggestions: suggestions(pattern),
+        }),
+        1 => Ok(matches),
+        _ if pattern.contains(['*', '?']) => Ok(matches),
+        _ => Err(LookupError::Ambiguous {
+            name: pattern.to_string(),
+            candidates: matches.iter().map(|demo| demo.name()).collect(),
+        }),
+    }
+}
+
+/// The demo names within a small edit distance of `name`, closest first.
+///
+/// The allowed distance grows with the length of the name, so short typos
+/// don't match everything.
+fn suggestions(name: &str) -> Vec<&'static str> {
+    let limit = (name.chars().count() / 3).max(1);
+    let mut close: Vec<(usize, &'static str)> = all()
+        .map(|demo| (edit_distance(name, demo.name()), demo.name()))
+        .filter(|&(distance, _)| distance <= limit)
+        .collect();
+    close.sort();
+    close.into_iter().take(3).map(|(_, name)| name).collect()
+}
+
+/// The Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Matches `name` against a glob where `*` is any run of characters and `?` is one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where to resume after the last `*`, if the rest fails to match
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    n = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(demos: Vec<&'static dyn Demo>) -> Vec<&'static str> {
+        demos.into_iter().map(|demo| demo.name()).collect()
+    }
+
+    #[test]
+    fn test_resolve_exact_prefix_and_glob() {
+        assert_eq!(names(resolve("hashmap").unwrap()), ["hashmap"]);
+        assert_eq!(names(resolve("conc").unwrap()), ["concurrency"]);
+        assert_eq!(names(resolve("hash*").unwrap()), ["hashmap", "hashset"]);
+        assert_eq!(names(resolve("?ector").unwrap()), ["vector"]);
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        assert_eq!(
+            resolve("hashmpa").err(),
+            Some(LookupError::Unknown {
+                name: "hashmpa".to_string(),
+                suggestions: vec!["hashmap"],
+            })
+        );
+        let err = resolve("c").err().unwrap();
+        assert!(matches!(err, LookupError::Ambiguous { .. }), "{:?}", err);
+        assert_eq!(resolve("zzz*").err().unwrap().to_string(), "Unknown demo: zzz*");
+    }
+
+    #[test]
+    fn test_edit_distance_and_glob() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert!(glob_match("*_*", "control_flow"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("hash?", "hashmap"));
+    }
+}
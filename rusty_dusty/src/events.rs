@@ -0,0 +1,220 @@
+//! # Demo Event Stream
+//!
+//! Turns a demo run into a stream of JSON events, one object per line, for
+//! scripts that would otherwise scrape the demo's text output:
+//!
+//! - `start` when the demo begins,
+//! - `section` for headings such as `=== Channels ===`,
+//! - `assertion` for each check reported as `assert passed: ...`,
+//! - `output` for every other line,
+//! - `panic` if the demo panics, and
+//! - `end` with the outcome and duration.
+
+use std::any::Any;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::demo::Demo;
+use crate::json::Object;
+use crate::params::Params;
+
+/// How demo output is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => Err(format!("Unknown format: {} (expected text or json)", other)),
+        }
+    }
+}
+
+/// What a single line of demo output represents.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Line<'a> {
+    Section(&'a str),
+    Assertion(&'a str),
+    Output(&'a str),
+}
+
+/// Classifies a line of demo output.
+///
+/// Headings are lines wrapped in `===` or `---`, and assertions are lines
+/// starting with `assert passed: `.
+pub fn classify(line: &str) -> Line<'_> {
+    let trimmed = line.trim();
+    for marker in ["===", "---"] {
+        if let Some(title) = trimmed
+            .strip_prefix(marker)
+            .and_then(|rest| rest.strip_suffix(marker))
+        {
+            if !title.trim().is_empty() {
+                return Line::Section(title.trim());
+            }
+        }
+    }
+
+    match trimmed.strip_prefix("assert passed: ") {
+        Some(check) => Line::Assertion(check),
+        None => Line::Output(line),
+    }
+}
+
+/// A `Write` sink that emits one JSON event per line written to it.
+pub struct EventWriter<'a> {
+    out: &'a mut dyn Write,
+    demo: &'static str,
+    partial: Vec<u8>,
+}
+
+impl<'a> EventWriter<'a> {
+    pub fn new(demo: &'static str, out: &'a mut dyn Write) -> Self {
+        EventWriter {
+            out,
+            demo,
+            partial: vec![],
+        }
+    }
+
+    /// Emits an event for any trailing output that did not end in a newline.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if !self.partial.is_empty() {
+            let line = std::mem::take(&mut self.partial);
+            self.emit_line(&String::from_utf8_lossy(&line))?;
+        }
+        Ok(())
+    }
+
+    fn emit_line(&mut self, line: &str) -> io::Result<()> {
+        let event = match classify(line) {
+            Line::Section(title) => self.event("section").string("title", title),
+            Line::Assertion(check) => self.event("assertion").string("check", check),
+            Line::Output(text) => self.event("output").string("line", text),
+        };
+        writeln!(self.out, "{}", event.finish())
+    }
+
+    fn event(&self, kind: &str) -> Object {
+        event(kind, self.demo)
+    }
+}
+
+impl Write for EventWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.partial.extend_from_slice(buf);
+        while let Some(end) = self.partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            self.emit_line(&String::from_utf8_lossy(&line[..end]))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+fn event(kind: &str, demo: &str) -> Object {
+    Object::new().string("event", kind).string("demo", demo)
+}
+
+/// Runs a demo with the given parameters, writing its event stream to `out`.
+///
+/// Panics are caught and reported as a `panic` event. Returns `true` if the demo
+/// finished without panicking.
+pub fn run_json(demo: &dyn Demo, params: &Params, out: &mut dyn Write) -> io::Result<bool> {
+    let start = event("start", demo.name())
+        .string("category", demo.category().name())
+        .finish();
+    writeln!(out, "{}", start)?;
+
+    let started = Instant::now();
+    let mut writer = EventWriter::new(demo.name(), out);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| demo.run_with(params, &mut writer)));
+    writer.finish()?;
+    let duration = started.elapsed();
+
+    let status = match result {
+        Ok(Ok(())) => "ok",
+        Ok(Err(err)) => return Err(err),
+        Err(payload) => {
+            let panic = event("panic", demo.name())
+                .string("message", &panic_message(payload.as_ref()))
+                .finish();
+            writeln!(out, "{}", panic)?;
+            "panicked"
+        }
+    };
+
+    let end = event("end", demo.name())
+        .string("status", status)
+        .number("duration_ms", duration.as_secs_f64() * 1000.0)
+        .finish();
+    writeln!(out, "{}", end)?;
+    out.flush()?;
+
+    Ok(status == "ok")
+}
+
+/// Extracts the message from a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("=== Channels ==="), Line::Section("Channels"));
+        assert_eq!(classify("--- Container Trait ---"), Line::Section("Container Trait"));
+        assert_eq!(classify("assert passed: sum == 15"), Line::Assertion("sum == 15"));
+        assert_eq!(classify("------"), Line::Output("------"));
+        assert_eq!(classify("Received: hi"), Line::Output("Received: hi"));
+    }
+
+    #[test]
+    fn test_event_writer_splits_lines() {
+        let mut out = Vec::new();
+        let mut writer = EventWriter::new("demo", &mut out);
+        write!(writer, "=== Title ===\nfirst ").unwrap();
+        writeln!(writer, "line").unwrap();
+        write!(writer, "tail").unwrap();
+        writer.finish().unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"event":"section","demo":"demo","title":"Title"}"#,
+                r#"{"event":"output","demo":"demo","line":"first line"}"#,
+                r#"{"event":"output","demo":"demo","line":"tail"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! # Exercises
+//!
+//! Demos can ship exercises for the learner to solve. Each exercise is a file of
+//! function stubs in the `exercises/` directory and a reference test suite kept
+//! out of sight in `src/checks/`. Checking an exercise builds the learner's file
+//! together with the reference tests in a scratch Cargo project, runs
+//! `cargo test` there and reports which cases pass.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::demo::{self, Demo};
+
+/// An exercise shipped by a demo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exercise {
+    /// The name used to select the exercise on the command line.
+    pub name: &'static str,
+    pub summary: &'static str,
+    /// The reference tests, which call the learner's functions by name.
+    pub tests: &'static str,
+}
+
+/// One reference test case and whether the learner's code passed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub name: String,
+    pub passed: bool,
+    /// The test's captured output when it failed, usually the panic message.
+    pub message: String,
+}
+
+/// The result of checking an exercise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The learner's file did not compile; holds the compiler output.
+    CompileError(String),
+    /// The tests ran; one entry per reference test.
+    Tested(Vec<Case>),
+}
+
+impl Outcome {
+    /// `true` if the code compiled and every case passed.
+    pub fn passed(&self) -> bool {
+        match self {
+            Outcome::CompileError(_) => false,
+            Outcome::Tested(cases) => cases.iter().all(|case| case.passed),
+        }
+    }
+}
+
+impl Exercise {
+    /// Where the learner's copy of the exercise lives.
+    pub fn path(&self) -> PathBuf {
+        exercises_dir().join(format!("{}.rs", self.name))
+    }
+
+    /// Builds the learner's file at `path` against the reference tests and runs them.
+    pub fn check(&self, path: &Path) -> io::Result<Outcome> {
+        let project = env::temp_dir().join("rusty_dusty_exercises").join(self.name);
+        fs::create_dir_all(&project)?;
+        fs::write(project.join("Cargo.toml"), manifest(self.name))?;
+        fs::write(project.join("lib.rs"), self.lib_source(&fs::canonicalize(path)?))?;
+
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let output = Command::new(cargo)
+            .args(["test", "--color", "never", "--"])
+            .args(["--test-threads", "1"])
+            .current_dir(&project)
+            .env_remove("CARGO_TARGET_DIR")
+            .env("RUST_BACKTRACE", "0")
+            .output()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !stdout.contains("running ") {
+            return Ok(Outcome::CompileError(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+        Ok(Outcome::Tested(parse_results(&stdout)))
+    }
+
+    /// The scratch crate's root: the learner's file as a module, re-exported so
+    /// the reference tests can reach its functions, followed by the tests.
+    fn lib_source(&self, learner: &Path) -> String {
+        format!(
+            "#[path = {:?}]\nmod learner;\npub use learner::*;\n\n\
+             #[cfg(test)]\nmod reference {{\n    use super::*;\n\n{}}}\n",
+            learner.display().to_string(),
+            self.tests
+        )
+    }
+}
+
+/// The directory the learner's exercise files live in.
+pub fn exercises_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("exercises")
+}
+
+/// Returns every exercise, in demo order.
+pub fn all() -> impl Iterator<Item = (&'static dyn Demo, &'static Exercise)> {
+    demo::all().flat_map(|demo| demo.exercises().iter().map(move |exercise| (demo, exercise)))
+}
+
+/// Looks up an exercise by its exact name, along with the demo that ships it.
+pub fn find(name: &str) -> Option<(&'static dyn Demo, &'static Exercise)> {
+    all().find(|(_, exercise)| exercise.name == name)
+}
+
+fn manifest(name: &str) -> String {
+    format!(
+        "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+         [lib]\npath = \"lib.rs\"\n\n[workspace]\n",
+        name
+    )
+}
+
+/// Reads the per-test results from libtest's output.
+///
+/// Each test reports `test reference::<name> ... ok` or `... FAILED`; failures
+/// are followed by a `---- reference::<name> stdout ----` section with their
+/// output.
+fn parse_results(stdout: &str) -> Vec<Case> {
+    let mut cases: Vec<Case> = stdout
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("test reference::")?;
+            let (name, result) = rest.split_once(" ... ")?;
+            Some(Case {
+                name: name.to_string(),
+                passed: result == "ok",
+                message: String::new(),
+            })
+        })
+        .collect();
+
+    let mut lines = stdout.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = line
+            .strip_prefix("---- reference::")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        else {
+            continue;
+        };
+        let message: Vec<&str> = lines
+            .by_ref()
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| !line.is_empty())
+            .filter(|line| !line.contains("panicked at") && !line.starts_with("note:"))
+            .collect();
+        if let Some(case) = cases.iter_mut().find(|case| case.name == name) {
+            case.message = message.join("\n");
+        }
+    }
+    cases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results() {
+        let stdout = "\nrunning 2 tests\n\
+                      test reference::adds ... ok\n\
+                      test reference::divides ... FAILED\n\n\
+                      failures:\n\n\
+                      ---- reference::divides stdout ----\n\n\
+                      thread 'reference::divides' panicked at lib.rs:9:5:\n\
+                      not yet implemented\n\
+                      note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n\n\
+                      failures:\n    reference::divides\n";
+        let cases = parse_results(stdout);
+        assert_eq!(cases.len(), 2);
+        assert!(cases[0].passed);
+        assert!(!cases[1].passed);
+        assert_eq!(cases[1].message, "not yet implemented");
+    }
+
+    #[test]
+    fn test_every_exercise_has_a_stub() {
+        assert!(all().count() >= 4);
+        for (_, exercise) in all() {
+            assert!(exercise.path().is_file(), "missing {}", exercise.path().display());
+            assert!(exercise.tests.contains("#[test]"), "{} has no tests", exercise.name);
+        }
+    }
+}
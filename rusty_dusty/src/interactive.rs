@@ -0,0 +1,491 @@
+//! # Interactive Menu
+//!
+//! A plain ANSI terminal menu for browsing and running demos. Demos are listed
+//! by category; pick one with the arrow keys and Enter, or by typing its number.
+//! The output is shown in a simple pager before returning to the menu, and every
+//! demo run in the session is kept in a history shown below the menu.
+//!
+//! Arrow keys need the terminal in non-canonical mode, which is switched on with
+//! `stty`. When stdin is not a terminal the menu falls back to reading numbers
+//! line by line.
+//!
+//! A lone Escape and the start of an arrow key's escape sequence look the same,
+//! so the terminal is read on a background thread by a `Keyboard`, which
+//! gives up on the rest of a sequence that does not arrive promptly.
+
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::demo::{Category, Demo};
+use crate::progress;
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const REVERSE: &str = "\x1b[7m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// How long to wait for the rest of an escape sequence after `ESC`.
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// A key press, decoded from the raw terminal input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Enter,
+    Backspace,
+    Escape,
+    Char(char),
+    Eof,
+}
+
+/// What the menu wants to do after handling a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Stay,
+    Run(usize),
+    Quit,
+}
+
+/// One demo run during this session.
+#[derive(Debug)]
+pub struct HistoryEntry {
+    pub name: &'static str,
+    pub duration: Duration,
+    pub passed: bool,
+}
+
+/// The menu state: the demos on offer, the highlighted row and the session history.
+pub struct Menu {
+    demos: Vec<&'static dyn Demo>,
+    selected: usize,
+    typed: String,
+    history: Vec<HistoryEntry>,
+}
+
+impl Menu {
+    pub fn new(demos: Vec<&'static dyn Demo>) -> Self {
+        Menu {
+            demos,
+            selected: 0,
+            typed: String::new(),
+            history: vec![],
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    /// Updates the menu for a key press.
+    ///
+    /// Typed digits build up a demo number; Enter runs that number if one was
+    /// typed and the highlighted demo otherwise.
+    pub fn handle(&mut self, key: Key) -> Selection {
+        match key {
+            Key::Up => {
+                self.typed.clear();
+                self.selected = self.selected.checked_sub(1).unwrap_or(self.demos.len() - 1);
+            }
+            Key::Down => {
+                self.typed.clear();
+                self.selected = (self.selected + 1) % self.demos.len();
+            }
+            Key::Char(c) if c.is_ascii_digit() => self.typed.push(c),
+            Key::Backspace => {
+                self.typed.pop();
+            }
+            Key::Escape => self.typed.clear(),
+            Key::Enter => {
+                let typed = std::mem::take(&mut self.typed);
+                if typed.is_empty() {
+                    return Selection::Run(self.selected);
+                }
+                match typed.parse::<usize>() {
+                    Ok(number) if (1..=self.demos.len()).contains(&number) => {
+                        self.selected = number - 1;
+                        return Selection::Run(self.selected);
+                    }
+                    _ => {}
+                }
+            }
+            Key::Char('q') | Key::Eof => return Selection::Quit,
+            Key::Char(_) => {}
+        }
+        Selection::Stay
+    }
+
+    /// Draws the menu, grouped by category, followed by the session history.
+    pub fn render(&self, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "{}{}Rusty-Dusty demos{}\n\n", CLEAR, BOLD, RESET)?;
+
+        let mut category: Option<Category> = None;
+        for (index, demo) in self.demos.iter().enumerate() {
+            if category != Some(demo.category()) {
+                if category.is_some() {
+                    writeln!(out)?;
+                }
+                category = Some(demo.category());
+                writeln!(out, "{}{}{}", BOLD, demo.category(), RESET)?;
+            }
+
+            let line = format!("{:>3}. {:<16} {}", index + 1, demo.name(), demo.summary());
+            if index == self.selected {
+                writeln!(out, "{}{}{}", REVERSE, line, RESET)?;
+            } else {
+                writeln!(out, "{}", line)?;
+            }
+        }
+        writeln!(out)?;
+
+        if !self.history.is_empty() {
+            writeln!(out, "{}This session:{}", BOLD, RESET)?;
+            for entry in &self.history {
+                let result = if entry.passed { "ok" } else { "panicked" };
+                writeln!(out, "  {:<16} {:<8} {}ms", entry.name, result, entry.duration.as_millis())?;
+            }
+            writeln!(out)?;
+        }
+
+        write!(
+            out,
+            "{}Up/Down to move, Enter to run, type a number to jump, q to quit{}\n> {}",
+            DIM, RESET, self.typed
+        )?;
+        out.flush()
+    }
+
+    /// Runs a demo into a buffer, recording it in the history.
+    ///
+    /// A panicking demo is caught so the menu keeps going.
+    pub fn run(&mut self, index: usize) -> String {
+        let demo = self.demos[index];
+        let mut buffer = Vec::new();
+
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| demo.run(&mut buffer)));
+        let duration = start.elapsed();
+
+        let mut output = String::from_utf8_lossy(&buffer).into_owned();
+        let passed = match result {
+            Ok(Ok(())) => true,
+            Ok(Err(err)) => {
+                output.push_str(&format!("\nError writing output: {}\n", err));
+                false
+            }
+            Err(_) => {
+                output.push_str("\nThe demo panicked.\n");
+                false
+            }
+        };
+
+        self.history.push(HistoryEntry {
+            name: demo.name(),
+            duration,
+            passed,
+        });
+        output
+    }
+}
+
+/// Decodes one key press from terminal input.
+///
+/// Arrow keys arrive as the escape sequences `ESC [ A` and `ESC [ B`. An `ESC`
+/// that is not followed straight away by the rest of a known sequence, i.e.
+/// when `input` runs out or reports [`io::ErrorKind::WouldBlock`], is
+/// [`Key::Escape`].
+pub fn read_key(input: &mut dyn Read) -> io::Result<Key> {
+    let Some(byte) = read_byte(input, true)? else {
+        return Ok(Key::Eof);
+    };
+
+    let key = match byte {
+        b'\n' | b'\r' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => match read_byte(input, false)? {
+            Some(b'[') => match read_byte(input, false)? {
+                Some(b'A') => Key::Up,
+                Some(b'B') => Key::Down,
+                _ => Key::Escape,
+            },
+            _ => Key::Escape,
+        },
+        other => Key::Char(other as char),
+    };
+    Ok(key)
+}
+
+/// Reads one byte, or `None` at the end of input. Unless `wait` is set, also
+/// gives up when `input` has nothing yet.
+fn read_byte(input: &mut dyn Read, wait: bool) -> io::Result<Option<u8>> {
+    let mut byte = [0u8; 1];
+    loop {
+        match input.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock && !wait => return Ok(None),
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted) => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Terminal input, read on a background thread so it can be waited on with a
+/// timeout.
+///
+/// Reads fail with [`io::ErrorKind::WouldBlock`] when nothing arrives within
+/// [`ESCAPE_TIMEOUT`].
+struct Keyboard {
+    bytes: Receiver<io::Result<u8>>,
+}
+
+impl Keyboard {
+    fn stdin() -> Keyboard {
+        let (sender, bytes) = mpsc::channel();
+        thread::spawn(move || {
+            for byte in io::stdin().lock().bytes() {
+                if sender.send(byte).is_err() {
+                    break;
+                }
+            }
+        });
+        Keyboard { bytes }
+    }
+}
+
+impl Read for Keyboard {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.bytes.recv_timeout(ESCAPE_TIMEOUT) {
+            Ok(byte) => {
+                buf[0] = byte?;
+                Ok(1)
+            }
+            Err(RecvTimeoutError::Timeout) => Err(io::ErrorKind::WouldBlock.into()),
+            Err(RecvTimeoutError::Disconnected) => Ok(0),
+        }
+    }
+}
+
+/// Switches the terminal out of line-buffered mode for as long as it is alive.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Option<RawMode> {
+        stty(&["-icanon", "-echo", "min", "1"]).then_some(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        stty(&["icanon", "echo"]);
+    }
+}
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// The number of rows in the terminal, falling back to 24.
+fn terminal_height() -> usize {
+    Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()
+        .and_then(|output| {
+            let size = String::from_utf8(output.stdout).ok()?;
+            size.split_whitespace().next()?.parse().ok()
+        })
+        .unwrap_or(24)
+}
+
+/// Shows `text` one screen at a time.
+fn page(text: &str, height: usize, input: &mut dyn Read, out: &mut dyn Write) -> io::Result<()> {
+    let lines: Vec<&str> = text.lines().collect();
+    let per_page = height.saturating_sub(2).max(1);
+
+    for (number, chunk) in lines.chunks(per_page).enumerate() {
+        write!(out, "{}", CLEAR)?;
+        for line in chunk {
+            writeln!(out, "{}", line)?;
+        }
+        let last = (number + 1) * per_page >= lines.len();
+        let prompt = if last {
+            "-- end (any key: back to menu) --"
+        } else {
+            "-- more (space: next page, q or Esc: back to menu) --"
+        };
+        write!(out, "{}{}{}", DIM, prompt, RESET)?;
+        out.flush()?;
+
+        match read_key(input)? {
+            Key::Char('q') | Key::Escape | Key::Eof => return Ok(()),
+            _ if last => return Ok(()),
+            _ => {}
+        }
+    }
+
+    if lines.is_empty() {
+        write!(out, "{}(no output) press any key{}", DIM, RESET)?;
+        out.flush()?;
+        read_key(input)?;
+    }
+    Ok(())
+}
+
+/// Runs the interactive menu until the user quits.
+pub fn run() -> io::Result<()> {
+    let mut menu = Menu::new(crate::demo::all().collect());
+    let mut stdout = io::stdout();
+
+    if !io::stdin().is_terminal() {
+        return run_line_mode(&mut menu, &mut io::stdin().lock(), &mut stdout);
+    }
+
+    let _raw = RawMode::enable();
+    let height = terminal_height();
+    let mut keyboard = Keyboard::stdin();
+
+    loop {
+        menu.render(&mut stdout)?;
+        match menu.handle(read_key(&mut keyboard)?) {
+            Selection::Stay => {}
+            Selection::Run(index) => {
+                let output = menu.run(index);
+                record_progress(&menu);
+                page(&output, height, &mut keyboard, &mut stdout)?;
+            }
+            Selection::Quit => break,
+        }
+    }
+
+    write!(stdout, "{}", CLEAR)?;
+    stdout.flush()
+}
+
+/// Records the demo just run as completed, if it passed.
+///
+/// Failing to save progress is not worth interrupting the menu for.
+fn record_progress(menu: &Menu) {
+    if let Some(entry) = menu.history().last().filter(|entry| entry.passed) {
+        let _ = progress::record_completed(entry.name);
+    }
+}
+
+/// Reads demo numbers one per line, for when stdin is not a terminal.
+fn run_line_mode(menu: &mut Menu, input: &mut dyn BufRead, out: &mut dyn Write) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        menu.render(out)?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        for c in line.trim().chars() {
+            if menu.handle(Key::Char(c)) == Selection::Quit {
+                return Ok(());
+            }
+        }
+        match menu.handle(Key::Enter) {
+            Selection::Run(index) => {
+                let output = menu.run(index);
+                record_progress(menu);
+                write!(out, "{}{}", CLEAR, output)?;
+            }
+            Selection::Quit => return Ok(()),
+            Selection::Stay => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu() -> Menu {
+        Menu::new(crate::demo::all().collect())
+    }
+
+    #[test]
+    fn test_read_key_decodes_arrows() {
+        let mut input: &[u8] = b"\x1b[A\x1b[B\nq";
+        assert_eq!(read_key(&mut input).unwrap(), Key::Up);
+        assert_eq!(read_key(&mut input).unwrap(), Key::Down);
+        assert_eq!(read_key(&mut input).unwrap(), Key::Enter);
+        assert_eq!(read_key(&mut input).unwrap(), Key::Char('q'));
+        assert_eq!(read_key(&mut input).unwrap(), Key::Eof);
+    }
+
+    /// Input that has `bytes` and then nothing more for now.
+    struct Pending<'a>(&'a [u8]);
+
+    impl Read for Pending<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.read(buf)? {
+                0 => Err(io::ErrorKind::WouldBlock.into()),
+                n => Ok(n),
+            }
+        }
+    }
+
+    #[test]
+    fn test_lone_escape_does_not_block() {
+        assert_eq!(read_key(&mut Pending(b"\x1b")).unwrap(), Key::Escape);
+        assert_eq!(read_key(&mut Pending(b"\x1b[")).unwrap(), Key::Escape);
+        assert_eq!(read_key(&mut Pending(b"\x1b[B")).unwrap(), Key::Down);
+
+        let mut input: &[u8] = b"\x1b";
+        assert_eq!(read_key(&mut input).unwrap(), Key::Escape);
+        assert_eq!(read_key(&mut input).unwrap(), Key::Eof);
+    }
+
+    #[test]
+    fn test_arrows_wrap_around() {
+        let mut menu = menu();
+        menu.handle(Key::Up);
+        assert_eq!(menu.selected(), menu.demos.len() - 1);
+        menu.handle(Key::Down);
+        assert_eq!(menu.selected(), 0);
+    }
+
+    #[test]
+    fn test_typed_number_selects_demo() {
+        let mut menu = menu();
+        menu.handle(Key::Char('1'));
+        menu.handle(Key::Char('2'));
+        assert_eq!(menu.handle(Key::Enter), Selection::Run(11));
+
+        menu.handle(Key::Char('9'));
+        menu.handle(Key::Char('9'));
+        assert_eq!(menu.handle(Key::Enter), Selection::Stay);
+        assert_eq!(menu.handle(Key::Char('q')), Selection::Quit);
+    }
+
+    #[test]
+    fn test_run_records_history() {
+        let mut menu = menu();
+        let index = menu.demos.iter().position(|demo| demo.name() == "option").unwrap();
+        let output = menu.run(index);
+        assert!(output.contains("Option Enum!"));
+        assert_eq!(menu.history().len(), 1);
+        assert_eq!(menu.history()[0].name, "option");
+        assert!(menu.history()[0].passed);
+    }
+}
@@ -0,0 +1,108 @@
+//! # JSON Writer
+//!
+//! A minimal, hand-rolled JSON object writer, just enough for flat event records
+//! without pulling in a serialization crate.
+
+use std::fmt::Write;
+
+/// Builds a single-line JSON object field by field.
+///
+/// ```
+/// use rusty_dusty::json::Object;
+///
+/// let json = Object::new().string("event", "start").number("line", 3).finish();
+/// assert_eq!(json, r#"{"event":"start","line":3}"#);
+/// ```
+#[derive(Debug, Default)]
+pub struct Object {
+    buffer: String,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Object::default()
+    }
+
+    /// Adds a string field, escaping the value.
+    pub fn string(mut self, key: &str, value: &str) -> Self {
+        self.key(key);
+        escape_into(&mut self.buffer, value);
+        self
+    }
+
+    /// Adds a numeric field.
+    pub fn number(mut self, key: &str, value: impl Into<f64>) -> Self {
+        self.key(key);
+        let value = value.into();
+        if value.is_finite() {
+            write!(self.buffer, "{}", value).unwrap();
+        } else {
+            self.buffer.push_str("null");
+        }
+        self
+    }
+
+    /// Adds a boolean field.
+    pub fn boolean(mut self, key: &str, value: bool) -> Self {
+        self.key(key);
+        self.buffer.push_str(if value { "true" } else { "false" });
+        self
+    }
+
+    /// Closes the object and returns the JSON text.
+    pub fn finish(self) -> String {
+        format!("{{{}}}", self.buffer)
+    }
+
+    fn key(&mut self, key: &str) {
+        if !self.buffer.is_empty() {
+            self.buffer.push(',');
+        }
+        escape_into(&mut self.buffer, key);
+        self.buffer.push(':');
+    }
+}
+
+/// Writes `value` as a quoted JSON string.
+fn escape_into(buffer: &mut String, value: &str) {
+    buffer.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(buffer, "\\u{:04x}", c as u32).unwrap(),
+            c => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escapes_strings() {
+        let json = Object::new().string("line", "say \"hi\"\\\n\t\u{1b}[0m 世界").finish();
+        assert_eq!(json, r#"{"line":"say \"hi\"\\\n\t\u001b[0m 世界"}"#);
+    }
+
+    #[test]
+    fn test_numbers_and_booleans() {
+        let json = Object::new()
+            .number("ms", 1.5)
+            .number("count", 3u32)
+            .number("bad", f64::NAN)
+            .boolean("ok", true)
+            .finish();
+        assert_eq!(json, r#"{"ms":1.5,"count":3,"bad":null,"ok":true}"#);
+    }
+
+    #[test]
+    fn test_empty_object() {
+        assert_eq!(Object::new().finish(), "{}");
+    }
+}
@@ -0,0 +1,21 @@
+//! # Rusty-Dusty
+//!
+//! A collection of small, runnable demos of Rust concepts, collections and
+//! advanced topics. Each demo is registered in [`demo`] and writes its output to
+//! an injected [`std::io::Write`] sink; the types the demos build on (such as
+//! `Mailbox`, `Character` and `Celsius`) are public so they can be reused.
+
+pub mod advanced;
+pub mod alloc_count;
+pub mod collections;
+pub mod concepts;
+pub mod demo;
+pub mod events;
+pub mod exercise;
+pub mod interactive;
+pub mod json;
+pub mod params;
+pub mod progress;
+pub mod runner;
+pub mod snapshot;
+pub mod trace;
@@ -1,61 +1,435 @@
 // Import necessary modules
 use std::env; // For accessing command-line arguments
+use std::io;
+use std::process;
 
-// Bring a specific function into scope with an alias
-use concepts::own::main as own_demo;
+// The demos themselves live in the library crate
+use rusty_dusty::demo::{self, Category, Demo};
+use rusty_dusty::events::{self, Format};
+use rusty_dusty::exercise::{self, Outcome};
+use rusty_dusty::params::Params;
+use rusty_dusty::progress::{self, Progress};
+use rusty_dusty::alloc_count::CountingAllocator;
+use rusty_dusty::{interactive, runner, snapshot};
 
-// Declare internal modules that contain various concepts and demos
-mod collections;
-mod concepts;
-mod advanced;
+// Counts allocations so the demos can report what their strategies cost
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
 
 /// Entry point of the program.
 /// This program executes selected demo modules based on command-line arguments.
 fn main() {
     // Collect command-line arguments, skipping the first one (program name)
-    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    // `--format json` may appear anywhere and applies to every demo run
+    let format = match take_format(&mut args) {
+        Ok(format) => format,
+        Err(err) => {
+            println!("{}", err);
+            process::exit(2);
+        }
+    };
 
     // If no arguments are passed, display usage instructions and return
     if args.is_empty() {
-        println!("Usage: cargo run -- <demo_name> [<demo_name> ...]");
-        println!("Example: cargo run -- variables data_types vector");
+        print_usage();
         return;
     }
 
-    // Iterate over the provided demo names and execute the matching module
-    for arg in &args {
-        match arg.as_str() {
-            // Basic Rust concepts
-            "variables" => concepts::variables::main(),
-            "data_types" => concepts::data_types::main(),
-            "array" => concepts::array::main(),
-            "tuples" => concepts::tuples::main(),
-            "strings" => concepts::strings::main(),
-            "control_flow" => concepts::control_flow::main(),
-            "errors" => concepts::errors::main(),
-            "option" => concepts::option::main(),
-            "compound_types" => concepts::compound_types::main(),
+    match args[0].as_str() {
+        "list" => match parse_category(&args[1..]) {
+            Ok(category) => list(category),
+            Err(err) => println!("{}", err),
+        },
+        "describe" => {
+            if args.len() == 1 {
+                println!("Usage: cargo run -- describe <demo_name> [<demo_name> ...]");
+            }
+            let mut all_found = true;
+            for name in &args[1..] {
+                match demo::resolve(name) {
+                    Ok(demos) => demos.into_iter().for_each(describe),
+                    Err(err) => {
+                        all_found = false;
+                        println!("{}", err);
+                    }
+                }
+            }
+            if !all_found {
+                process::exit(1);
+            }
+        }
+        "all" => {
+            let reports = runner::run_all();
+            if !runner::print_summary(&reports) {
+                process::exit(1);
+            }
+        }
+        "menu" => {
+            if let Err(err) = interactive::run() {
+                eprintln!("Menu failed: {}", err);
+                process::exit(1);
+            }
+        }
+        "progress" => {
+            if let Err(err) = show_progress() {
+                println!("Could not read progress: {}", err);
+                process::exit(1);
+            }
+        }
+        "next" => {
+            if let Err(err) = recommend_next() {
+                println!("Could not read progress: {}", err);
+                process::exit(1);
+            }
+        }
+        "exercise" => match &args[1..] {
+            [] => list_exercises(),
+            [name] => {
+                if !check_exercise(name) {
+                    process::exit(1);
+                }
+            }
+            _ => println!("Usage: cargo run -- exercise [<exercise_name>]"),
+        },
+        "snapshot" => {
+            let update = args[1..].iter().any(|arg| arg == "--update");
+            if !check_snapshots(update) {
+                process::exit(1);
+            }
+        }
+        _ => {
+            if !run(&args, format) {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Usage: cargo run -- <demo_name> [--<param> <value> ...] [<demo_name> ...] [--format text|json]");
+    println!("       cargo run -- <demo_name> --help");
+    println!("       cargo run -- --category <category> [--format text|json]");
+    println!("       cargo run -- all");
+    println!("       cargo run -- menu");
+    println!("       cargo run -- exercise [<exercise_name>]");
+    println!("       cargo run -- progress");
+    println!("       cargo run -- next");
+    println!("       cargo run -- snapshot [--update]");
+    println!("       cargo run -- list [--category <category>]");
+    println!("       cargo run -- describe <demo_name>");
+    println!("Example: cargo run -- variables data_types vector");
+    println!("         cargo run -- conc 'hash*'");
+    println!("         cargo run -- concurrency --threads 8 --jobs 100");
+}
+
+/// Removes a `--format <format>` pair from the arguments, defaulting to text.
+fn take_format(args: &mut Vec<String>) -> Result<Format, String> {
+    let Some(index) = args.iter().position(|arg| arg == "--format") else {
+        return Ok(Format::Text);
+    };
+    if index + 1 >= args.len() {
+        return Err("Missing format after --format".to_string());
+    }
+    let format = args[index + 1].parse()?;
+    args.drain(index..=index + 1);
+    Ok(format)
+}
+
+/// Parses an optional `--category <name>` pair.
+fn parse_category(args: &[String]) -> Result<Option<Category>, String> {
+    match args {
+        [] => Ok(None),
+        [flag, name] if flag == "--category" => name.parse().map(Some),
+        _ => Err("Usage: cargo run -- list [--category <category>]".to_string()),
+    }
+}
+
+/// Prints every demo with its summary, grouped by category.
+fn list(filter: Option<Category>) {
+    for category in Category::ALL {
+        if filter.is_some_and(|wanted| wanted != category) {
+            continue;
+        }
+        println!("{}:", category);
+        for demo in category.demos() {
+            println!("  {:<16} {}", demo.name(), demo.summary());
+        }
+    }
+}
+
+fn describe(demo: &dyn Demo) {
+    println!("{}", demo.name());
+    println!("  category: {}", demo.category());
+    println!("  summary:  {}", demo.summary());
+    if !demo.prerequisites().is_empty() {
+        println!("  after:    {}", demo.prerequisites().join(", "));
+    }
+    print_params(demo);
+    for exercise in demo.exercises() {
+        println!("  exercise: {} ({})", exercise.name, exercise.summary);
+    }
+}
+
+/// Lists the parameters a demo accepts, if any.
+fn print_params(demo: &dyn Demo) {
+    if demo.params().is_empty() {
+        return;
+    }
+    println!("  parameters:");
+    for param in demo.params() {
+        println!(
+            "    --{:<12} {} ({}, default {})",
+            param.name,
+            param.help,
+            param.range(),
+            param.default
+        );
+    }
+}
+
+/// Runs the named demos in order; `--category <name>` runs a whole category.
+///
+/// A demo may be named exactly, by a unique prefix or by a glob such as `hash*`.
+/// It may be followed by `--<param> <value>` pairs for that demo, or by `--help`
+/// to list its parameters instead of running it.
+///
+/// Returns `false` if any demo failed or any name did not resolve.
+fn run(args: &[String], format: Format) -> bool {
+    let mut all_passed = true;
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        if arg == "--category" {
+            let category = match args.next().map(|name| name.parse::<Category>()) {
+                Some(Ok(category)) => category,
+                Some(Err(err)) => {
+                    println!("{}", err);
+                    all_passed = false;
+                    continue;
+                }
+                None => {
+                    println!("Missing category after --category");
+                    all_passed = false;
+                    continue;
+                }
+            };
+            for demo in category.demos() {
+                all_passed &= run_demo(*demo, &Params::defaults(demo.params()), format);
+            }
+            continue;
+        }
+
+        // Everything up to the next demo name belongs to this demo
+        let mut demo_args = vec![];
+        while let Some(flag) = args.next_if(|arg| arg.starts_with("--") && *arg != "--category") {
+            demo_args.push(flag.clone());
+            if flag != "--help" {
+                demo_args.extend(args.next().cloned());
+            }
+        }
+
+        let demos = match demo::resolve(arg) {
+            Ok(demos) => demos,
+            Err(err) => {
+                all_passed = false;
+                println!("{}", err);
+                continue;
+            }
+        };
+        for demo in demos {
+            if demo_args.iter().any(|arg| arg == "--help") {
+                describe(demo);
+                continue;
+            }
+            match Params::parse(demo.params(), &demo_args) {
+                Ok(params) => all_passed &= run_demo(demo, &params, format),
+                Err(err) => {
+                    all_passed = false;
+                    println!("{}: {}", demo.name(), err);
+                    if demo.params().is_empty() {
+                        println!("  {} takes no parameters", demo.name());
+                    } else {
+                        print_params(demo);
+                    }
+                }
+            }
+        }
+    }
+    all_passed
+}
+
+/// Loads the learner's progress, or says why it can't.
+fn load_progress() -> io::Result<Option<Progress>> {
+    match progress::path() {
+        Some(path) => Progress::load(&path).map(Some),
+        None => {
+            println!("Progress tracking is off (RUSTY_DUSTY_PROGRESS is empty).");
+            Ok(None)
+        }
+    }
+}
 
-            // Ownership and borrowing
-            "own" => own_demo(),
+/// Prints coverage per category, with the date each demo was last completed.
+fn show_progress() -> io::Result<()> {
+    let Some(progress) = load_progress()? else {
+        return Ok(());
+    };
 
-            // Collections
-            "vector" => collections::vector::demo(),
-            "hashmap" => collections::hashmap::demo(),
-            "btreemap" => collections::btreemap::demo(),
-            "hashset" => collections::hashset::demo(),
+    for category in Category::ALL {
+        let (done, total) = progress.coverage(category);
+        println!("{}: {}/{} ({}%)", category, done, total, done * 100 / total.max(1));
+        for demo in category.demos() {
+            match progress.completed_at(demo.name()) {
+                Some(at) => println!("  [x] {:<16} {}", demo.name(), progress::format_date(at)),
+                None => println!("  [ ] {}", demo.name()),
+            }
+        }
+    }
+
+    if !progress.exercises().is_empty() {
+        println!("exercises:");
+        for result in progress.exercises() {
+            let outcome = if result.passed { "pass" } else { "fail" };
+            println!(
+                "  {:<20} {:<4} {}",
+                result.exercise,
+                outcome,
+                progress::format_date(result.at)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Recommends the next demo to work through, following the prerequisites.
+fn recommend_next() -> io::Result<()> {
+    let Some(progress) = load_progress()? else {
+        return Ok(());
+    };
+
+    match progress.next() {
+        Some(demo) => {
+            println!("Next up: {} ({})", demo.name(), demo.category());
+            println!("  {}", demo.summary());
+            if !demo.prerequisites().is_empty() {
+                println!("  builds on: {}", demo.prerequisites().join(", "));
+            }
+            println!("Run it with: cargo run -- {}", demo.name());
+        }
+        None => println!("You have completed every demo."),
+    }
+    Ok(())
+}
 
-            // Intermediate concepts
-            "generics" => concepts::generics::demo(),
-            "traits" => concepts::traits::trait_demo(),
-            "lifetimes" => concepts::lifetimes::demo(),
+/// Lists every exercise with the learner's latest result.
+fn list_exercises() {
+    let progress = progress::path()
+        .and_then(|path| Progress::load(&path).ok())
+        .unwrap_or_default();
+
+    for (demo, exercise) in exercise::all() {
+        let status = match progress.exercises().iter().find(|result| result.exercise == exercise.name) {
+            Some(result) if result.passed => "pass",
+            Some(_) => "fail",
+            None => "-",
+        };
+        println!("  {:<20} {:<4} [{}] {}", exercise.name, status, demo.name(), exercise.summary);
+    }
+    println!("Check one with: cargo run -- exercise <exercise_name>");
+}
+
+/// Checks the learner's solution to an exercise; returns `true` if every case passes.
+fn check_exercise(name: &str) -> bool {
+    let Some((_, exercise)) = exercise::find(name) else {
+        println!("Unknown exercise: {}", name);
+        return false;
+    };
+
+    let path = exercise.path();
+    println!("Checking {} ...", path.display());
+    let outcome = match exercise.check(&path) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            println!("Could not run the checks: {}", err);
+            return false;
+        }
+    };
+
+    match &outcome {
+        Outcome::CompileError(errors) => {
+            println!("{}", errors.trim_end());
+            println!("{} does not compile yet.", exercise.name);
+        }
+        Outcome::Tested(cases) => {
+            for case in cases {
+                if case.passed {
+                    println!("  [pass] {}", case.name);
+                } else {
+                    println!("  [FAIL] {}", case.name);
+                    for line in case.message.lines() {
+                        println!("         {}", line);
+                    }
+                }
+            }
+            let passed = cases.iter().filter(|case| case.passed).count();
+            println!("{}: {}/{} cases pass", exercise.name, passed, cases.len());
+        }
+    }
+
+    if let Some(path) = progress::path() {
+        let saved = Progress::load(&path).and_then(|mut progress| {
+            progress.record_exercise(exercise.name, outcome.passed(), progress::now());
+            progress.save(&path)
+        });
+        if let Err(err) = saved {
+            eprintln!("{}: could not save progress: {}", exercise.name, err);
+        }
+    }
+    outcome.passed()
+}
+
+/// Checks every demo against its recorded snapshot; returns `true` if all match.
+fn check_snapshots(update: bool) -> bool {
+    let mut all_matched = true;
+    for demo in demo::all() {
+        match snapshot::check(demo, update) {
+            Ok(snapshot::Status::Matched) => println!("{:<16} ok", demo.name()),
+            Ok(snapshot::Status::Updated) => println!("{:<16} updated", demo.name()),
+            Ok(snapshot::Status::Missing) => {
+                all_matched = false;
+                println!("{:<16} missing (run with --update to record it)", demo.name());
+            }
+            Ok(snapshot::Status::Changed(diff)) => {
+                all_matched = false;
+                println!("{:<16} changed\n{}", demo.name(), diff);
+            }
+            Err(err) => {
+                all_matched = false;
+                println!("{:<16} error: {}", demo.name(), err);
+            }
+        }
+    }
+    all_matched
+}
 
-            // Advanced topics
-            "iter_closure" => advanced::iter_closure::demo(),
-            "concurrency" => advanced::concurrency::demo(),
+/// Runs a single demo with its output going to stdout; returns `true` on success.
+///
+/// A successful run is recorded in the learner's progress.
+fn run_demo(demo: &dyn Demo, params: &Params, format: Format) -> bool {
+    let result = match format {
+        Format::Text => demo.run_with(params, &mut io::stdout()).map(|()| true),
+        Format::Json => events::run_json(demo, params, &mut io::stdout()),
+    };
+    let passed = result.unwrap_or_else(|err| {
+        eprintln!("{}: failed to write output: {}", demo.name(), err);
+        false
+    });
 
-            // Fallback for unknown arguments
-            _ => println!("Unknown demo: {}", arg),
+    if passed {
+        if let Err(err) = progress::record_completed(demo.name()) {
+            eprintln!("{}: could not save progress: {}", demo.name(), err);
         }
     }
+    passed
 }
@@ -0,0 +1,280 @@
+//! # Demo Parameters
+//!
+//! Demos can declare the knobs they accept as a schema of [`Param`]s. Values
+//! are passed on the command line after the demo name, e.g.
+//! `cargo run -- concurrency --threads 8 --jobs 100`, and parsed into [`Params`]
+//! with defaults filled in for anything not given.
+
+use std::fmt;
+use std::time::Duration;
+
+/// The type of value a parameter takes, with its valid range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    /// A whole number between `min` and `max`, inclusive.
+    Integer { min: u64, max: u64 },
+    /// A duration such as `500ms` or `2s`, up to `max`.
+    Duration { max: Duration },
+    /// One of a fixed set of words.
+    Choice { options: &'static [&'static str] },
+}
+
+/// One parameter a demo accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: ParamKind,
+    /// The default, written the same way as on the command line.
+    pub default: &'static str,
+}
+
+impl Param {
+    /// A short description of the accepted values, e.g. `1..=64` or `duration <= 60s`.
+    pub fn range(&self) -> String {
+        match self.kind {
+            ParamKind::Integer { min, max } => format!("{}..={}", min, max),
+            ParamKind::Duration { max } => format!("duration <= {:?}", max),
+            ParamKind::Choice { options } => format!("one of {}", options.join(", ")),
+        }
+    }
+
+    /// Parses and validates a value for this parameter.
+    pub fn parse(&self, raw: &str) -> Result<Value, ParamError> {
+        let invalid = |reason: String| ParamError::Invalid {
+            name: self.name,
+            value: raw.to_string(),
+            reason,
+        };
+
+        match self.kind {
+            ParamKind::Integer { min, max } => {
+                let value: u64 = raw
+                    .parse()
+                    .map_err(|_| invalid("expected a whole number".to_string()))?;
+                if value < min || value > max {
+                    return Err(invalid(format!("must be in {}", self.range())));
+                }
+                Ok(Value::Integer(value))
+            }
+            ParamKind::Duration { max } => {
+                let value = parse_duration(raw)
+                    .ok_or_else(|| invalid("expected a duration like 500ms or 2s".to_string()))?;
+                if value.is_zero() || value > max {
+                    return Err(invalid(format!("must be positive and at most {:?}", max)));
+                }
+                Ok(Value::Duration(value))
+            }
+            ParamKind::Choice { options } => options
+                .iter()
+                .find(|option| **option == raw)
+                .map(|option| Value::Choice(option))
+                .ok_or_else(|| invalid(format!("must be {}", self.range()))),
+        }
+    }
+}
+
+/// A parsed parameter value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Integer(u64),
+    Duration(Duration),
+    Choice(&'static str),
+}
+
+/// Errors from parsing demo parameters.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParamError {
+    Unknown(String),
+    MissingValue(&'static str),
+    Invalid {
+        name: &'static str,
+        value: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamError::Unknown(name) => write!(f, "unknown parameter `{}`", name),
+            ParamError::MissingValue(name) => write!(f, "missing value for `--{}`", name),
+            ParamError::Invalid {
+                name,
+                value,
+                reason,
+            } => write!(f, "invalid value `{}` for `--{}`: {}", value, name, reason),
+        }
+    }
+}
+
+/// The parameter values a demo runs with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: Vec<(&'static str, Value)>,
+}
+
+impl Params {
+    /// The default value of every parameter in the schema.
+    pub fn defaults(schema: &[Param]) -> Params {
+        let values = schema
+            .iter()
+            .map(|param| {
+                let value = param
+                    .parse(param.default)
+                    .unwrap_or_else(|err| panic!("bad default for `{}`: {}", param.name, err));
+                (param.name, value)
+            })
+            .collect();
+        Params { values }
+    }
+
+    /// Parses `--name value` pairs against the schema, starting from the defaults.
+    pub fn parse(schema: &[Param], args: &[String]) -> Result<Params, ParamError> {
+        let mut params = Params::defaults(schema);
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let name = arg.strip_prefix("--").unwrap_or(arg);
+            let param = schema
+                .iter()
+                .find(|param| param.name == name)
+                .ok_or_else(|| ParamError::Unknown(arg.clone()))?;
+            let raw = args.next().ok_or(ParamError::MissingValue(param.name))?;
+            let value = param.parse(raw)?;
+
+            for entry in params.values.iter_mut().filter(|(name, _)| *name == param.name) {
+                entry.1 = value;
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Returns an integer parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the demo did not declare `name` as an integer parameter.
+    pub fn integer(&self, name: &str) -> u64 {
+        match self.get(name) {
+            Value::Integer(value) => value,
+            other => panic!("parameter `{}` is not an integer: {:?}", name, other),
+        }
+    }
+
+    /// Returns an integer parameter as a `usize`.
+    pub fn count(&self, name: &str) -> usize {
+        self.integer(name) as usize
+    }
+
+    /// Returns a duration parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the demo did not declare `name` as a duration parameter.
+    pub fn duration(&self, name: &str) -> Duration {
+        match self.get(name) {
+            Value::Duration(value) => value,
+            other => panic!("parameter `{}` is not a duration: {:?}", name, other),
+        }
+    }
+
+    /// Returns a choice parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the demo did not declare `name` as a choice parameter.
+    pub fn choice(&self, name: &str) -> &'static str {
+        match self.get(name) {
+            Value::Choice(value) => value,
+            other => panic!("parameter `{}` is not a choice: {:?}", name, other),
+        }
+    }
+
+    fn get(&self, name: &str) -> Value {
+        self.values
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| *value)
+            .unwrap_or_else(|| panic!("undeclared parameter `{}`", name))
+    }
+}
+
+/// Parses `500ms`, `2s` or a bare number of seconds.
+fn parse_duration(raw: &str) -> Option<Duration> {
+    if let Some(millis) = raw.strip_suffix("ms") {
+        millis.parse().ok().map(Duration::from_millis)
+    } else {
+        let seconds = raw.strip_suffix('s').unwrap_or(raw);
+        seconds.parse().ok().map(Duration::from_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &[Param] = &[
+        Param {
+            name: "threads",
+            help: "Worker threads",
+            kind: ParamKind::Integer { min: 1, max: 64 },
+            default: "3",
+        },
+        Param {
+            name: "limit",
+            help: "Time limit",
+            kind: ParamKind::Duration {
+                max: Duration::from_secs(60),
+            },
+            default: "1s",
+        },
+        Param {
+            name: "mode",
+            help: "Mode",
+            kind: ParamKind::Choice {
+                options: &["fast", "slow"],
+            },
+            default: "fast",
+        },
+    ];
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let params = Params::defaults(SCHEMA);
+        assert_eq!(params.integer("threads"), 3);
+        assert_eq!(params.duration("limit"), Duration::from_secs(1));
+        assert_eq!(params.choice("mode"), "fast");
+    }
+
+    #[test]
+    fn test_parse_overrides_defaults() {
+        let params = Params::parse(SCHEMA, &args(&["--threads", "8", "--limit", "250ms", "--mode", "slow"])).unwrap();
+        assert_eq!(params.count("threads"), 8);
+        assert_eq!(params.duration("limit"), Duration::from_millis(250));
+        assert_eq!(params.choice("mode"), "slow");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Params::parse(SCHEMA, &args(&["--jobs", "4"])),
+            Err(ParamError::Unknown("--jobs".to_string()))
+        );
+        assert_eq!(
+            Params::parse(SCHEMA, &args(&["--threads"])),
+            Err(ParamError::MissingValue("threads"))
+        );
+        let err = Params::parse(SCHEMA, &args(&["--threads", "0"])).unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `0` for `--threads`: must be in 1..=64");
+        assert!(Params::parse(SCHEMA, &args(&["--limit", "soon"])).is_err());
+        assert!(Params::parse(SCHEMA, &args(&["--limit", "0ms"])).is_err());
+        let err = Params::parse(SCHEMA, &args(&["--mode", "turbo"])).unwrap_err();
+        assert_eq!(err.to_string(), "invalid value `turbo` for `--mode`: must be one of fast, slow");
+    }
+}
@@ -0,0 +1,249 @@
+//! # Learner Progress
+//!
+//! Keeps a small local record of which demos someone has worked through and how
+//! their exercises went, so `progress` can show coverage per category and
+//! `next` can recommend what to look at next.
+//!
+//! The record lives in `~/.rusty_dusty/progress.txt`, or wherever the
+//! `RUSTY_DUSTY_PROGRESS` environment variable points; setting it to an empty
+//! string turns tracking off. The file is plain text, one tab-separated entry
+//! per line: `completed <demo> <time>` or `exercise <name> pass|fail <time>`,
+//! with times in seconds since the Unix epoch.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::demo::{self, Category, Demo};
+
+/// When a demo was last run to completion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub demo: String,
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+}
+
+/// The latest result of an exercise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExerciseResult {
+    pub exercise: String,
+    pub passed: bool,
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+}
+
+/// Everything recorded about a learner.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Progress {
+    completed: Vec<Completion>,
+    exercises: Vec<ExerciseResult>,
+}
+
+impl Progress {
+    /// Reads the progress file, starting fresh if it does not exist yet.
+    pub fn load(path: &Path) -> io::Result<Progress> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Progress::parse(&text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Parses the progress file format, skipping lines it does not understand.
+    pub fn parse(text: &str) -> Progress {
+        let mut progress = Progress::default();
+        for line in text.lines() {
+            match line.split('\t').collect::<Vec<_>>().as_slice() {
+                ["completed", demo, at] => {
+                    if let Ok(at) = at.parse() {
+                        progress.mark_completed(demo, at);
+                    }
+                }
+                ["exercise", exercise, result, at] => {
+                    if let (Ok(at), "pass" | "fail") = (at.parse(), *result) {
+                        progress.record_exercise(exercise, *result == "pass", at);
+                    }
+                }
+                _ => {}
+            }
+        }
+        progress
+    }
+
+    /// Writes the progress file, replacing it in one step.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, self.to_text())?;
+        fs::rename(&temp, path)
+    }
+
+    /// The progress file contents.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for completion in &self.completed {
+            text.push_str(&format!("completed\t{}\t{}\n", completion.demo, completion.at));
+        }
+        for result in &self.exercises {
+            let outcome = if result.passed { "pass" } else { "fail" };
+            text.push_str(&format!("exercise\t{}\t{}\t{}\n", result.exercise, outcome, result.at));
+        }
+        text
+    }
+
+    /// Records that a demo was run, keeping only the latest time.
+    pub fn mark_completed(&mut self, demo: &str, at: u64) {
+        match self.completed.iter_mut().find(|completion| completion.demo == demo) {
+            Some(completion) => completion.at = completion.at.max(at),
+            None => self.completed.push(Completion {
+                demo: demo.to_string(),
+                at,
+            }),
+        }
+    }
+
+    /// Records the result of an exercise attempt, replacing any earlier one.
+    pub fn record_exercise(&mut self, exercise: &str, passed: bool, at: u64) {
+        match self.exercises.iter_mut().find(|result| result.exercise == exercise) {
+            Some(result) if result.at > at => {}
+            Some(result) => {
+                result.passed = passed;
+                result.at = at;
+            }
+            None => self.exercises.push(ExerciseResult {
+                exercise: exercise.to_string(),
+                passed,
+                at,
+            }),
+        }
+    }
+
+    pub fn completed(&self) -> &[Completion] {
+        &self.completed
+    }
+
+    pub fn exercises(&self) -> &[ExerciseResult] {
+        &self.exercises
+    }
+
+    /// When a demo was last completed, if ever.
+    pub fn completed_at(&self, demo: &str) -> Option<u64> {
+        self.completed
+            .iter()
+            .find(|completion| completion.demo == demo)
+            .map(|completion| completion.at)
+    }
+
+    pub fn is_completed(&self, demo: &str) -> bool {
+        self.completed_at(demo).is_some()
+    }
+
+    /// How many of a category's demos are completed, out of how many.
+    pub fn coverage(&self, category: Category) -> (usize, usize) {
+        let demos = category.demos();
+        let done = demos.iter().filter(|demo| self.is_completed(demo.name())).count();
+        (done, demos.len())
+    }
+
+    /// The first demo, in registry order, that is not yet completed but whose
+    /// prerequisites all are. `None` once every demo is done.
+    pub fn next(&self) -> Option<&'static dyn Demo> {
+        demo::all().find(|demo| {
+            !self.is_completed(demo.name())
+                && demo
+                    .prerequisites()
+                    .iter()
+                    .all(|prerequisite| self.is_completed(prerequisite))
+        })
+    }
+}
+
+/// Where progress is kept, or `None` if tracking is turned off.
+pub fn path() -> Option<PathBuf> {
+    match env::var_os("RUSTY_DUSTY_PROGRESS") {
+        Some(path) if path.is_empty() => None,
+        Some(path) => Some(PathBuf::from(path)),
+        None => {
+            let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+            Some(PathBuf::from(home).join(".rusty_dusty").join("progress.txt"))
+        }
+    }
+}
+
+/// The current time in seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Records a completed demo in the progress file, if tracking is on.
+pub fn record_completed(demo: &str) -> io::Result<()> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    let mut progress = Progress::load(&path)?;
+    progress.mark_completed(demo, now());
+    progress.save(&path)
+}
+
+/// Formats a Unix timestamp as a UTC `YYYY-MM-DD` date.
+pub fn format_date(at: u64) -> String {
+    // Days since 1970-01-01 to a civil date, after Howard Hinnant's `civil_from_days`
+    let days = (at / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut progress = Progress::default();
+        progress.mark_completed("own", 100);
+        progress.mark_completed("own", 50);
+        progress.record_exercise("own_mailbox", false, 200);
+        progress.record_exercise("own_mailbox", true, 300);
+
+        let text = progress.to_text();
+        assert_eq!(text, "completed\town\t100\nexercise\town_mailbox\tpass\t300\n");
+        assert_eq!(Progress::parse(&format!("{}garbage\ncompleted\tx\n", text)), progress);
+    }
+
+    #[test]
+    fn test_next_follows_prerequisites() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.next().map(|demo| demo.name()), Some("variables"));
+
+        for demo in demo::all().filter(|demo| demo.name() != "lifetimes") {
+            progress.mark_completed(demo.name(), 1);
+        }
+        assert_eq!(progress.next().map(|demo| demo.name()), Some("lifetimes"));
+        assert_eq!(progress.coverage(Category::Concepts).0 + 1, Category::Concepts.demos().len());
+
+        progress.mark_completed("lifetimes", 1);
+        assert!(progress.next().is_none());
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_792_281_600), "2026-10-18");
+    }
+}
@@ -0,0 +1,142 @@
+//! # Demo Runner
+//!
+//! Runs every registered demo in its own child process, so a panic in one demo
+//! cannot take down the rest, and summarizes the results in a table.
+
+use std::env;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::demo::{self, Category, Demo};
+
+/// How a single demo run ended.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Passed,
+    Failed(String),
+}
+
+/// The result of running one demo.
+#[derive(Debug)]
+pub struct Report {
+    pub name: &'static str,
+    pub category: Category,
+    pub duration: Duration,
+    pub output_bytes: usize,
+    pub outcome: Outcome,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Passed
+    }
+}
+
+/// Runs every registered demo in order and returns one report per demo.
+///
+/// Each demo is run by re-invoking the current executable, so this is meant to
+/// be called from the `rusty_dusty` binary.
+pub fn run_all() -> Vec<Report> {
+    demo::all().map(run_isolated).collect()
+}
+
+/// Runs a demo by re-invoking this binary with the demo's name.
+///
+/// The child's stdout is captured and measured; its stderr is searched for a
+/// panic message if the child exits unsuccessfully. Progress tracking is turned
+/// off in the child, since a batch run is not the learner working through a demo.
+fn run_isolated(demo: &dyn Demo) -> Report {
+    let start = Instant::now();
+    let output = env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .arg(demo.name())
+            .env("RUSTY_DUSTY_PROGRESS", "")
+            .stdin(Stdio::null())
+            .output()
+    });
+    let duration = start.elapsed();
+
+    let (output_bytes, outcome) = match output {
+        Ok(output) if output.status.success() => (output.stdout.len(), Outcome::Passed),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = panic_message(&stderr).unwrap_or_else(|| output.status.to_string());
+            (output.stdout.len(), Outcome::Failed(reason))
+        }
+        Err(err) => (0, Outcome::Failed(format!("could not start demo: {}", err))),
+    };
+
+    Report {
+        name: demo.name(),
+        category: demo.category(),
+        duration,
+        output_bytes,
+        outcome,
+    }
+}
+
+/// Extracts the panic message from a child's stderr.
+///
+/// The default panic hook prints `thread '...' panicked at <location>:` followed
+/// by the message on the next line.
+fn panic_message(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    lines.find(|line| line.contains("panicked at"))?;
+    lines.next().map(|message| message.trim().to_string())
+}
+
+/// Prints the summary table and returns `true` if every demo passed.
+pub fn print_summary(reports: &[Report]) -> bool {
+    println!();
+    println!(
+        "{:<16} {:<12} {:<6} {:>10} {:>10}",
+        "DEMO", "CATEGORY", "RESULT", "TIME", "OUTPUT"
+    );
+    for report in reports {
+        let result = if report.passed() { "ok" } else { "FAILED" };
+        println!(
+            "{:<16} {:<12} {:<6} {:>8}ms {:>8} B",
+            report.name,
+            report.category.name(),
+            result,
+            report.duration.as_millis(),
+            report.output_bytes
+        );
+    }
+
+    let failures: Vec<&Report> = reports.iter().filter(|report| !report.passed()).collect();
+    println!(
+        "\n{} demos: {} passed, {} failed",
+        reports.len(),
+        reports.len() - failures.len(),
+        failures.len()
+    );
+    for report in &failures {
+        if let Outcome::Failed(reason) = &report.outcome {
+            println!("  {}: {}", report.name, reason);
+        }
+    }
+
+    failures.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let stderr = "\nthread 'main' (42) panicked at src/collections/vector.rs:32:19:\n\
+                      index out of bounds: the len is 1 but the index is 1\n\
+                      note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+        assert_eq!(
+            panic_message(stderr).as_deref(),
+            Some("index out of bounds: the len is 1 but the index is 1")
+        );
+    }
+
+    #[test]
+    fn test_panic_message_missing() {
+        assert_eq!(panic_message("Error: something went wrong"), None);
+    }
+}
@@ -0,0 +1,134 @@
+//! # Snapshot Testing
+//!
+//! Records the output of every demo in `tests/snapshots/<demo>.txt` and checks
+//! later runs against it. Output that changes between runs is hidden first using
+//! the demo's [`Mask`]s. Run with `--update` (or `UPDATE_SNAPSHOTS=1 cargo test`)
+//! to re-bless the recorded snapshots.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::demo::{Demo, Mask};
+
+/// The result of checking one demo against its snapshot.
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Matched,
+    Updated,
+    Missing,
+    Changed(String),
+}
+
+/// The directory snapshots are stored in.
+pub fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots")
+}
+
+/// Runs a demo into a buffer and returns its masked output.
+pub fn capture(demo: &dyn Demo) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    demo.run(&mut buffer)?;
+    Ok(normalize(&String::from_utf8_lossy(&buffer), demo.masks()))
+}
+
+/// Compares a demo's output with its snapshot, rewriting the snapshot if `update` is set.
+pub fn check(demo: &dyn Demo, update: bool) -> io::Result<Status> {
+    let actual = capture(demo)?;
+    let path = snapshot_dir().join(format!("{}.txt", demo.name()));
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => Some(expected),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+
+    match expected {
+        Some(expected) if expected == actual => Ok(Status::Matched),
+        _ if update => {
+            fs::create_dir_all(snapshot_dir())?;
+            fs::write(&path, actual)?;
+            Ok(Status::Updated)
+        }
+        Some(expected) => Ok(Status::Changed(diff(&expected, &actual))),
+        None => Ok(Status::Missing),
+    }
+}
+
+/// Applies masks to raw demo output.
+///
+/// The result always ends with a newline so snapshots are stable even for
+/// demos whose last write was a `write!` without one.
+pub fn normalize(output: &str, masks: &[Mask]) -> String {
+    let mut lines: Vec<String> = output.lines().map(|line| mask_line(line, masks)).collect();
+
+    if masks.contains(&Mask::Unordered) {
+        for block in lines.split_mut(|line| line.is_empty()) {
+            block.sort();
+        }
+    }
+
+    let mut normalized = lines.join("\n");
+    normalized.push('\n');
+    normalized
+}
+
+fn mask_line(line: &str, masks: &[Mask]) -> String {
+    for mask in masks {
+        if let Mask::After(marker) = mask {
+            if let Some(index) = line.find(marker) {
+                return format!("{}<masked>", &line[..index + marker.len()]);
+            }
+        }
+    }
+    line.to_string()
+}
+
+/// A simple line-by-line diff, listing each line that differs.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut report = String::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        let (old, new) = (expected.get(i), actual.get(i));
+        if old == new {
+            continue;
+        }
+        report.push_str(&format!("  line {}:\n", i + 1));
+        if let Some(old) = old {
+            report.push_str(&format!("  - {}\n", old));
+        }
+        if let Some(new) = new {
+            report.push_str(&format!("  + {}\n", new));
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_after_marker() {
+        let output = "Counting speed per second: 19716852\nDone";
+        let masks = [Mask::After("per second: ")];
+        assert_eq!(
+            normalize(output, &masks),
+            "Counting speed per second: <masked>\nDone\n"
+        );
+    }
+
+    #[test]
+    fn test_unordered_sorts_within_blocks() {
+        let output = "b\na\n\nd\nc";
+        assert_eq!(normalize(output, &[Mask::Unordered]), "a\nb\n\nc\nd\n");
+    }
+
+    #[test]
+    fn test_diff_reports_changed_lines() {
+        assert_eq!(diff("a\nb\n", "a\nc\nd\n"), "  line 2:\n  - b\n  + c\n  line 3:\n  + d\n");
+    }
+}
@@ -0,0 +1,323 @@
+//! # Ownership Tracing
+//!
+//! [`Tracked<T>`] wraps a value and records what happens to it on a per-thread
+//! timeline: when it is created, cloned, borrowed through a guard, moved and
+//! dropped. Demos print the timeline to show Rust's ownership rules at work,
+//! e.g. `Mailbox#1 moved into send_with_refactor` followed later by
+//! `Mailbox#1 dropped`.
+//!
+//! Moves are plain memory copies that run no code, so they are recorded only
+//! where the demo says so, with [`Tracked::move_into`] or [`Tracked::pass`].
+
+use std::any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+
+/// What happened to a tracked value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Created,
+    Cloned { from: String },
+    Borrowed,
+    BorrowedMut,
+    /// A shared or mutable borrow's guard went out of scope.
+    Released,
+    Moved { into: String },
+    Returned { from: String },
+    Dropped,
+}
+
+/// One entry on the timeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The value's type and number, e.g. `Mailbox#1`.
+    pub label: String,
+    pub action: Action,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ", self.label)?;
+        match &self.action {
+            Action::Created => write!(f, "created"),
+            Action::Cloned { from } => write!(f, "cloned from {}", from),
+            Action::Borrowed => write!(f, "borrowed"),
+            Action::BorrowedMut => write!(f, "borrowed mutably"),
+            Action::Released => write!(f, "borrow released"),
+            Action::Moved { into } => write!(f, "moved into {}", into),
+            Action::Returned { from } => write!(f, "returned from {}", from),
+            Action::Dropped => write!(f, "dropped"),
+        }
+    }
+}
+
+thread_local! {
+    static TIMELINE: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+    /// The last number handed out for each type name.
+    static COUNTERS: RefCell<BTreeMap<&'static str, usize>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+fn record(label: &str, action: Action) {
+    let event = Event {
+        label: label.to_string(),
+        action,
+    };
+    TIMELINE.with_borrow_mut(|timeline| timeline.push(event));
+}
+
+/// A fresh label for a value of type `T`, e.g. `Dog#3`.
+fn next_label<T>() -> String {
+    let name = any::type_name::<T>().rsplit("::").next().unwrap_or("value");
+    let number = COUNTERS.with_borrow_mut(|counters| {
+        let counter = counters.entry(name).or_default();
+        *counter += 1;
+        *counter
+    });
+    format!("{}#{}", name, number)
+}
+
+/// Removes and returns the events recorded on this thread so far.
+pub fn take_timeline() -> Vec<Event> {
+    TIMELINE.take()
+}
+
+/// Writes out every event recorded so far, one per line.
+pub fn write_timeline(out: &mut dyn Write) -> io::Result<()> {
+    for event in take_timeline() {
+        writeln!(out, "{}", event)?;
+    }
+    Ok(())
+}
+
+/// Clears the timeline and restarts numbering, so each demo run starts at `#1`.
+pub fn reset() {
+    TIMELINE.take();
+    COUNTERS.take();
+}
+
+/// A value whose lifecycle is recorded on the timeline.
+pub struct Tracked<T> {
+    label: String,
+    /// `None` once the value has been moved out.
+    value: Option<T>,
+}
+
+impl<T> Tracked<T> {
+    pub fn new(value: T) -> Self {
+        let label = next_label::<T>();
+        record(&label, Action::Created);
+        Tracked {
+            label,
+            value: Some(value),
+        }
+    }
+
+    /// Wraps a value handed back by a function, as a new tracked value.
+    pub fn returned_from(function: &str, value: T) -> Self {
+        let label = next_label::<T>();
+        record(
+            &label,
+            Action::Returned {
+                from: function.to_string(),
+            },
+        );
+        Tracked {
+            label,
+            value: Some(value),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Borrows the value until the guard is dropped.
+    pub fn borrow(&self) -> Borrowed<'_, T> {
+        record(&self.label, Action::Borrowed);
+        Borrowed { tracked: self }
+    }
+
+    /// Borrows the value mutably until the guard is dropped.
+    pub fn borrow_mut(&mut self) -> BorrowedMut<'_, T> {
+        record(&self.label, Action::BorrowedMut);
+        BorrowedMut { tracked: self }
+    }
+
+    /// Gives the value away; it is no longer tracked.
+    pub fn move_into(mut self, owner: &str) -> T {
+        record(
+            &self.label,
+            Action::Moved {
+                into: owner.to_string(),
+            },
+        );
+        self.value.take().expect("tracked value is present until moved")
+    }
+
+    /// Moves the value into `function` and tracks what it returns under the same
+    /// label, for functions that hand back the value they were given.
+    pub fn pass(mut self, function: &str, f: impl FnOnce(T) -> T) -> Self {
+        record(
+            &self.label,
+            Action::Moved {
+                into: function.to_string(),
+            },
+        );
+        let value = f(self.value.take().expect("tracked value is present until moved"));
+        record(
+            &self.label,
+            Action::Returned {
+                from: function.to_string(),
+            },
+        );
+        self.value = Some(value);
+        self
+    }
+
+    fn get(&self) -> &T {
+        self.value.as_ref().expect("tracked value is present until moved")
+    }
+
+    fn get_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("tracked value is present until moved")
+    }
+}
+
+impl<T: Clone> Clone for Tracked<T> {
+    fn clone(&self) -> Self {
+        let label = next_label::<T>();
+        record(
+            &label,
+            Action::Cloned {
+                from: self.label.clone(),
+            },
+        );
+        Tracked {
+            label,
+            value: Some(self.get().clone()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({:?})", self.label, self.value)
+    }
+}
+
+/// Records the drop, then drops the value itself.
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        if self.value.is_some() {
+            record(&self.label, Action::Dropped);
+        }
+    }
+}
+
+/// A shared borrow of a tracked value; releasing it is recorded.
+pub struct Borrowed<'a, T> {
+    tracked: &'a Tracked<T>,
+}
+
+impl<T> Deref for Borrowed<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.tracked.get()
+    }
+}
+
+impl<T> Drop for Borrowed<'_, T> {
+    fn drop(&mut self) {
+        record(&self.tracked.label, Action::Released);
+    }
+}
+
+/// A mutable borrow of a tracked value; releasing it is recorded.
+pub struct BorrowedMut<'a, T> {
+    tracked: &'a mut Tracked<T>,
+}
+
+impl<T> Deref for BorrowedMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.tracked.get()
+    }
+}
+
+impl<T> DerefMut for BorrowedMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.tracked.get_mut()
+    }
+}
+
+impl<T> Drop for BorrowedMut<'_, T> {
+    fn drop(&mut self) {
+        record(&self.tracked.label, Action::Released);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Probe(u32);
+
+    impl Clone for Probe {
+        fn clone(&self) -> Self {
+            Probe(self.0 + 1)
+        }
+    }
+
+    fn timeline() -> Vec<String> {
+        take_timeline().iter().map(Event::to_string).collect()
+    }
+
+    #[test]
+    fn test_records_lifecycle_in_order() {
+        reset();
+        let mut first = Tracked::new(Probe(1));
+        {
+            let copy = first.clone();
+            assert_eq!(copy.borrow().0, 2);
+        }
+        first.borrow_mut().0 = 10;
+        let first = first.pass("double", |probe| Probe(probe.0 * 2));
+        assert_eq!(first.borrow().0, 20);
+        let probe = first.move_into("a vector");
+        assert_eq!(probe.0, 20);
+
+        assert_eq!(
+            timeline(),
+            [
+                "Probe#1 created",
+                "Probe#2 cloned from Probe#1",
+                "Probe#2 borrowed",
+                "Probe#2 borrow released",
+                "Probe#2 dropped",
+                "Probe#1 borrowed mutably",
+                "Probe#1 borrow released",
+                "Probe#1 moved into double",
+                "Probe#1 returned from double",
+                "Probe#1 borrowed",
+                "Probe#1 borrow released",
+                "Probe#1 moved into a vector",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reset_restarts_numbering() {
+        reset();
+        drop(Tracked::new(Probe(1)));
+        reset();
+        let returned = Tracked::returned_from("make", Probe(1));
+        assert_eq!(returned.label(), "Probe#1");
+        drop(returned);
+        assert_eq!(timeline(), ["Probe#1 returned from make", "Probe#1 dropped"]);
+    }
+}
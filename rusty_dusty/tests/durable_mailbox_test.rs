@@ -0,0 +1,165 @@
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use rusty_dusty::concepts::own::durable::DurableMailbox;
+use rusty_dusty::concepts::own::{CubeSat, Message};
+
+/// A fresh log path for one test.
+fn log_path(test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rusty_dusty_{}_{}.log", test, process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn message(to: u64, content: &str) -> Message {
+    Message {
+        to,
+        content: content.to_string(),
+    }
+}
+
+fn contents(mailbox: &mut DurableMailbox, sat_id: u64) -> Vec<String> {
+    let sat = CubeSat { id: sat_id };
+    let mut received = vec![];
+    while let Some(msg) = mailbox.deliver(&sat).unwrap() {
+        received.push(msg.content);
+    }
+    received
+}
+
+#[test]
+fn test_reopening_replays_posts_and_deliveries() {
+    let path = log_path("replay");
+    {
+        let mut mailbox = DurableMailbox::open(&path).unwrap();
+        mailbox.post(message(1, "a")).unwrap();
+        mailbox.post(message(2, "x")).unwrap();
+        mailbox.post(message(1, "b")).unwrap();
+        assert_eq!(mailbox.deliver(&CubeSat { id: 1 }).unwrap().unwrap().content, "a");
+    }
+
+    let mut mailbox = DurableMailbox::open(&path).unwrap();
+    assert_eq!(mailbox.recovery().records, 4);
+    assert_eq!(mailbox.recovery().discarded_bytes, 0);
+    assert_eq!(contents(&mut mailbox, 1), ["b"]);
+    assert_eq!(contents(&mut mailbox, 2), ["x"]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_truncated_record_keeps_every_complete_message() {
+    let path = log_path("truncated");
+    {
+        let mut mailbox = DurableMailbox::open(&path).unwrap();
+        mailbox.post(message(1, "first")).unwrap();
+        mailbox.post(message(1, "second")).unwrap();
+        mailbox.post(message(1, "cut off")).unwrap();
+    }
+
+    // Chop the last record in half, as if the process died mid-write
+    let len = fs::metadata(&path).unwrap().len();
+    OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 5).unwrap();
+
+    let mut mailbox = DurableMailbox::open(&path).unwrap();
+    assert_eq!(mailbox.recovery().records, 2);
+    assert!(mailbox.recovery().discarded_bytes > 0);
+    assert!(!mailbox.recovery().corrupt);
+
+    // The log was cut back to the last good record, so new posts replay too
+    mailbox.post(message(1, "after recovery")).unwrap();
+    drop(mailbox);
+    let mut mailbox = DurableMailbox::open(&path).unwrap();
+    assert_eq!(contents(&mut mailbox, 1), ["first", "second", "after recovery"]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_checksum_catches_corruption() {
+    let path = log_path("corrupt");
+    {
+        let mut mailbox = DurableMailbox::open(&path).unwrap();
+        mailbox.post(message(4, "good")).unwrap();
+        mailbox.post(message(4, "flipped")).unwrap();
+    }
+
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0x20;
+    fs::write(&path, bytes).unwrap();
+
+    let mut mailbox = DurableMailbox::open(&path).unwrap();
+    assert!(mailbox.recovery().corrupt);
+    assert_eq!(contents(&mut mailbox, 4), ["good"]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_damaged_middle_record_is_an_error() {
+    let path = log_path("middle");
+    {
+        let mut mailbox = DurableMailbox::open(&path).unwrap();
+        mailbox.post(message(4, "first")).unwrap();
+        mailbox.post(message(4, "damaged")).unwrap();
+        mailbox.post(message(4, "last")).unwrap();
+    }
+    let good = fs::read(&path).unwrap();
+    // Each record is an 8-byte header, a tag, the recipient and the content
+    let second = 8 + 1 + 8 + "first".len();
+
+    // A flipped content byte fails the checksum
+    let mut bytes = good.clone();
+    bytes[second + 8 + 9] ^= 0x20;
+    fs::write(&path, &bytes).unwrap();
+    let err = DurableMailbox::open(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().ends_with(&format!("damaged record at byte {}", second)), "{}", err);
+    assert_eq!(fs::read(&path).unwrap(), bytes, "the log is left alone");
+
+    // A length running past the end of the log is not mistaken for a torn write
+    let mut bytes = good;
+    bytes[second] = 0xff;
+    fs::write(&path, &bytes).unwrap();
+    let err = DurableMailbox::open(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(fs::read(&path).unwrap(), bytes);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_compaction_drops_delivered_records() {
+    let path = log_path("compact");
+    let mut mailbox = DurableMailbox::open(&path).unwrap();
+    mailbox.set_compact_threshold(10);
+
+    for i in 0..20 {
+        mailbox.post(message(i % 2, &format!("msg {}", i))).unwrap();
+    }
+    let before = fs::metadata(&path).unwrap().len();
+    assert_eq!(contents(&mut mailbox, 0).len(), 10);
+    mailbox.deliver(&CubeSat { id: 1 }).unwrap();
+
+    // 31 records were written; compaction ran once the 7th delivery left
+    // 14 dead records against 13 live ones, leaving 13 + 4 later records
+    assert_eq!(mailbox.log_records(), 17);
+    assert!(fs::metadata(&path).unwrap().len() < before);
+
+    drop(mailbox);
+    let mut mailbox = DurableMailbox::open(&path).unwrap();
+    let rest = contents(&mut mailbox, 1);
+    assert_eq!(rest.len(), 9);
+    assert_eq!(rest[0], "msg 3");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_durable_demo_recovers_after_torn_write() {
+    let mut out = Vec::new();
+    rusty_dusty::concepts::own::durable::main(3, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.contains("Replayed 4 records, dropped 12 bytes from the end of the log"));
+    assert!(out.contains("CubeSat 3 received: Durable msg for CubeSat 3!"));
+    assert!(out.ends_with("Mailbox empty: true\n"));
+}
@@ -0,0 +1,294 @@
+use rusty_dusty::advanced::concurrency;
+use rusty_dusty::alloc_count::{self, CountingAllocator};
+use rusty_dusty::concepts::compound_types::world::{MoveError, World};
+use rusty_dusty::concepts::compound_types::{Action, Character, CharacterClass, Position};
+use rusty_dusty::concepts::generics::largest;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rusty_dusty::concepts::own::{self, GroundStation, Mailbox, Message, RelayStation};
+use rusty_dusty::concepts::traits::{Celsius, Fahrenheit};
+use rusty_dusty::demo::{self, Category};
+use rusty_dusty::exercise::{self, Outcome};
+
+// The own demo and its tests report allocation counts
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_mailbox_delivers_to_recipient() {
+    let base = GroundStation;
+    let mut mailbox = Mailbox::new();
+    let sat = base.connect(7);
+
+    base.send_with_reference(&mut mailbox, Message { to: 7, content: "ping".to_string() });
+    base.send_with_reference(&mut mailbox, Message { to: 8, content: "other".to_string() });
+    assert_eq!(mailbox.len(), 2);
+
+    let msg = sat.recv(&mut mailbox).expect("message for CubeSat 7");
+    assert_eq!(msg.content, "ping");
+    assert!(sat.recv(&mut mailbox).is_none(), "only one message was sent to CubeSat 7");
+    assert_eq!(mailbox.len(), 1);
+}
+
+#[test]
+fn test_mailbox_is_fifo_per_recipient() {
+    let base = GroundStation;
+    let mut mailbox = Mailbox::new();
+    for (to, content) in [(1, "a"), (2, "x"), (1, "b"), (1, "c")] {
+        mailbox.post(Message { to, content: content.to_string() });
+    }
+
+    assert_eq!(mailbox.len(), 4);
+    assert_eq!(mailbox.pending_for(1), 3);
+    assert_eq!(mailbox.pending_for(9), 0);
+    assert_eq!(mailbox.peek_for(1).map(|msg| msg.content.as_str()), Some("a"));
+
+    let sat = base.connect(1);
+    assert_eq!(sat.recv(&mut mailbox).unwrap().content, "a");
+    let rest: Vec<String> = mailbox.drain_for(1).map(|msg| msg.content).collect();
+    assert_eq!(rest, ["b", "c"]);
+    assert!(sat.recv(&mut mailbox).is_none());
+    assert_eq!(mailbox.len(), 1);
+}
+
+#[test]
+fn test_every_send_method_delivers() {
+    let base = GroundStation;
+    let sat = base.connect(1);
+    let msg = |content: &str| Message { to: 1, content: content.to_string() };
+
+    let mut mailbox = Mailbox::new();
+    base.send_with_reference(&mut mailbox, msg("reference"));
+    mailbox = base.send_with_clone(mailbox, msg("clone"));
+    mailbox = base.send_with_refactor(mailbox, msg("refactor"));
+    let shared = Rc::new(RefCell::new(mailbox));
+    base.send_with_rc(Rc::clone(&shared), msg("rc"));
+
+    let mut received = vec![];
+    while let Some(msg) = sat.recv(&mut shared.borrow_mut()) {
+        received.push(msg.content);
+    }
+    received.sort();
+    assert_eq!(received, ["clone", "rc", "refactor", "reference"]);
+}
+
+#[test]
+fn test_relay_stations_share_one_mailbox() {
+    let mailbox = Rc::new(RefCell::new(Mailbox::new()));
+    let houston = RelayStation::new("Houston", &mailbox);
+    let kourou = RelayStation::new("Kourou", &mailbox);
+    let sat = houston.connect(4);
+
+    kourou.send(Message { to: 4, content: "from Kourou".to_string() });
+    houston.send(Message { to: 4, content: "from Houston".to_string() });
+    assert_eq!(mailbox.borrow().len(), 2);
+    assert_eq!(sat.station().map(|station| station.name.clone()).as_deref(), Some("Houston"));
+    assert!(sat.recv().is_some());
+
+    // The satellite's weak link does not keep its station alive
+    drop(houston);
+    assert!(sat.station().is_none());
+    assert!(sat.recv().is_none(), "no station left to receive through");
+    assert_eq!(mailbox.borrow().len(), 1);
+}
+
+#[test]
+fn test_threaded_simulation_accounts_for_every_message() {
+    let sat_ids: Vec<u64> = (1..=6).collect();
+    let report = own::threaded::simulate_threaded(&sat_ids);
+
+    // Three messages each; two uplinked per pass; CubeSats 3 and 6 take only one
+    assert_eq!(report.delivered(), 10);
+    assert_eq!(report.lost, 2);
+    assert_eq!(report.pending, 6);
+    assert!(report
+        .acks
+        .iter()
+        .filter(|ack| ack.sat_id == 1)
+        .map(|ack| ack.content.as_str())
+        .eq(["Command 1 for CubeSat 1", "Command 2 for CubeSat 1"]));
+}
+
+#[test]
+fn test_reliable_demo_dead_letters_unacked_messages() {
+    let mut out = Vec::new();
+    own::reliable::main(6, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.contains("CubeSat 6 got #6 (attempt 3), no ack: battery flat"));
+    assert!(out.contains("#3 for CubeSat 3 expired at t=30s after 3 attempts"));
+    assert!(out.ends_with("2 dead, 0 still in flight\n"));
+}
+
+#[test]
+fn test_scheduled_demo_waits_for_contact_windows() {
+    let mut out = Vec::new();
+    own::schedule::main(2, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.contains("Connecting at t=0s failed: CubeSat 1 is out of contact until t=10s"));
+    assert!(out.contains("--- Pass of CubeSat 2 at t=20s ---\nUplinked 27 bytes: Downlink images (CubeSat 2)"));
+    assert!(out.ends_with("31 of 56 bytes used, 0 queued for the next pass\n"));
+}
+
+#[test]
+fn test_broadcast_allocates_content_once() {
+    let mut out = Vec::new();
+    own::broadcast::main(50, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.contains("Post 2 to group `even` reaches"));
+    assert!(out.contains("String cloned per recipient: 51\n"));
+    let broadcast: usize = out
+        .lines()
+        .find_map(|line| line.strip_prefix("Rc<str> broadcast:"))
+        .map(|count| count.trim().parse().unwrap())
+        .unwrap();
+    assert!(broadcast < 51 / 2, "{} allocations", broadcast);
+}
+
+#[test]
+fn test_clone_method_costs_more_than_refactor() {
+    let base = GroundStation;
+    let msg = |to| Message {
+        to,
+        content: format!("Hello, CubeSat {}!", to),
+    };
+    let mut mailbox = Mailbox::new();
+    for to in 1..=5 {
+        mailbox.post(msg(to));
+    }
+
+    let (mailbox, cloned) = alloc_count::measure(|| base.send_with_clone(mailbox, msg(1)));
+    let (_, moved) = alloc_count::measure(|| base.send_with_refactor(mailbox, msg(2)));
+    assert_eq!(cloned.clones, 6);
+    assert_eq!(moved.clones, 0);
+    assert!(cloned.bytes > moved.bytes);
+}
+
+#[test]
+fn test_trace_demo_narrates_moves_and_drops() {
+    let mut out = Vec::new();
+    own::trace::main(2, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.contains("Mailbox#1 moved into send_with_clone\nMessage#3 moved into send_with_clone\nMailbox#2 returned from send_with_clone\n"));
+    assert!(out.contains("Mailbox#2 moved into send_with_refactor"));
+    assert!(out.ends_with("--- End of Scope ---\nMailbox#2 dropped\n"));
+    assert!(!out.contains("Mailbox#1 dropped"));
+}
+
+#[test]
+fn test_keep_map_routes_around_walls() {
+    let world = World::load(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("files/keep.map")).unwrap();
+    assert_eq!((world.width(), world.height()), (20, 10));
+
+    let mut rogue = Character::new("Bilbo".to_string(), CharacterClass::Rogue, 1);
+    rogue.position = Position(1, 1);
+    assert_eq!(rogue.move_to(&world, Position(7, 4)), Err(MoveError::Blocked(Position(7, 4))));
+    assert!(rogue.move_to(&world, Position(12, 3)).is_ok());
+    assert!(world.render(&[&rogue]).lines().nth(3).unwrap().contains("#B.#"));
+    assert!(!world.line_of_sight(Position(9, 3), Position(12, 3)));
+}
+
+#[test]
+fn test_character_actions() {
+    let apprentice = Character::new("Radagast".to_string(), CharacterClass::Mage, 1);
+    assert_eq!((apprentice.health, apprentice.mana), (60, 80));
+
+    // Six levels of mage growth: 5 health and 10 mana each
+    let mut mage = Character::new("Gandalf".to_string(), CharacterClass::Mage, 7);
+    assert_eq!((mage.health, mage.mana), (90, 140));
+
+    let spell = Action::CastSpell { spell_name: "Fireball".to_string(), mana_cost: 30, target: None };
+    assert_eq!(mage.perform_action(spell), "Gandalf casts Fireball!");
+    assert_eq!(mage.mana, 110);
+
+    let potion = Action::UseItem { item: "Healing Potion".to_string() };
+    assert_eq!(potion.describe(), "Use Healing Potion");
+    assert_eq!(mage.perform_action(potion), "Gandalf has no Healing Potion!");
+}
+
+#[test]
+fn test_celsius_from_fahrenheit() {
+    let c: Celsius = Fahrenheit(212.0).into();
+    assert!((c.0 - 100.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_largest() {
+    assert_eq!(largest(&[1, 5, 3, 9, 2]), 9);
+    assert_eq!(largest(&['g', 'z', 'a']), 'z');
+}
+
+#[test]
+fn test_shared_state_counts_every_thread() {
+    let mut out = Vec::new();
+    concurrency::shared_state_example(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("Final counter value: 3"), "unexpected output: {}", out);
+}
+
+#[test]
+fn test_registry_names_are_unique() {
+    let mut names: Vec<&str> = demo::all().map(|demo| demo.name()).collect();
+    let total = names.len();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), total, "duplicate demo names in the registry");
+
+    for category in Category::ALL {
+        assert!(category.demos().iter().all(|demo| demo.category() == category));
+    }
+}
+
+#[test]
+fn test_prerequisites_come_earlier_in_the_registry() {
+    let names: Vec<&str> = demo::all().map(|demo| demo.name()).collect();
+    for (index, demo) in demo::all().enumerate() {
+        for prerequisite in demo.prerequisites() {
+            let position = names.iter().position(|name| name == prerequisite);
+            assert!(
+                position.is_some_and(|position| position < index),
+                "{} depends on {}, which is not registered before it",
+                demo.name(),
+                prerequisite
+            );
+        }
+    }
+}
+
+#[test]
+fn test_exercise_check_runs_reference_tests() {
+    let (_, exercise) = exercise::find("option_basics").unwrap();
+    let solution = std::env::temp_dir().join("rusty_dusty_option_basics_solution.rs");
+    std::fs::write(
+        &solution,
+        "pub fn first_even(numbers: &[i32]) -> Option<i32> {\n\
+             numbers.iter().copied().find(|n| n % 2 == 0)\n\
+         }\n\
+         pub fn checked_divide(a: i32, b: i32) -> Option<i32> {\n\
+             a.checked_div(b)\n\
+         }\n\
+         pub fn name_length(name: Option<&str>) -> usize {\n\
+             name.map_or(0, str::len)\n\
+         }\n\
+         // Deliberately wrong: forgets to upper-case\n\
+         pub fn initial(name: Option<&str>) -> Option<char> {\n\
+             name.and_then(|name| name.chars().next())\n\
+         }\n",
+    )
+    .unwrap();
+
+    let Outcome::Tested(cases) = exercise.check(&solution).unwrap() else {
+        panic!("solution did not compile");
+    };
+    let failed: Vec<&str> = cases
+        .iter()
+        .filter(|case| !case.passed)
+        .map(|case| case.name.as_str())
+        .collect();
+    assert_eq!(cases.len(), 6);
+    assert_eq!(failed, ["initial_is_upper_case"]);
+}
@@ -0,0 +1,124 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rusty_dusty::concepts::own::protocol::{Delivery, Frame, GroundServer, SatClient, REGISTER_TIMEOUT};
+use rusty_dusty::concepts::own::Message;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn message(to: u64, content: &str) -> Message {
+    Message {
+        to,
+        content: content.to_string(),
+    }
+}
+
+fn recv(client: &mut SatClient) -> String {
+    client
+        .recv_timeout(TIMEOUT)
+        .expect("read from server")
+        .expect("connection open")
+        .content
+}
+
+#[test]
+fn test_messages_reach_connected_clients() {
+    let server = GroundServer::bind().unwrap();
+    let mut one = SatClient::connect(server.local_addr(), 1).unwrap();
+    let mut two = SatClient::connect(server.local_addr(), 2).unwrap();
+    assert!(server.wait_for(1, true, TIMEOUT));
+    assert!(server.wait_for(2, true, TIMEOUT));
+
+    assert_eq!(server.send(message(2, "for two")), Delivery::Sent);
+    assert_eq!(server.send(message(1, "for one")), Delivery::Sent);
+    assert_eq!(recv(&mut one), "for one");
+    assert_eq!(recv(&mut two), "for two");
+}
+
+#[test]
+fn test_queued_messages_flush_in_order_on_reconnect() {
+    let server = GroundServer::bind().unwrap();
+    let mut client = SatClient::connect(server.local_addr(), 5).unwrap();
+    assert!(server.wait_for(5, true, TIMEOUT));
+    assert_eq!(server.send(message(5, "online")), Delivery::Sent);
+    assert_eq!(recv(&mut client), "online");
+
+    client.disconnect();
+    assert!(server.wait_for(5, false, TIMEOUT));
+    assert_eq!(server.send(message(5, "first")), Delivery::Queued);
+    assert_eq!(server.send(message(5, "second")), Delivery::Queued);
+    assert_eq!(server.pending_for(5), 2);
+
+    let mut client = SatClient::connect(server.local_addr(), 5).unwrap();
+    assert_eq!(recv(&mut client), "first");
+    assert_eq!(recv(&mut client), "second");
+    assert_eq!(server.pending_for(5), 0);
+}
+
+#[test]
+fn test_new_connection_replaces_old_one() {
+    let server = GroundServer::bind().unwrap();
+    let mut old = SatClient::connect(server.local_addr(), 9).unwrap();
+    assert!(server.wait_for(9, true, TIMEOUT));
+    let mut new = SatClient::connect(server.local_addr(), 9).unwrap();
+
+    // The old connection is closed once the new one has registered
+    assert!(old.recv_timeout(TIMEOUT).unwrap().is_none());
+    assert_eq!(server.send(message(9, "latest")), Delivery::Sent);
+    assert_eq!(recv(&mut new), "latest");
+}
+
+#[test]
+fn test_registration_split_across_writes() {
+    let server = GroundServer::bind().unwrap();
+    let frame = Frame::Register { sat_id: 42 }.encode().unwrap();
+
+    let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+    stream.set_nodelay(true).unwrap();
+    for piece in frame.chunks(3) {
+        stream.write_all(piece).unwrap();
+        stream.flush().unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(server.wait_for(42, true, TIMEOUT));
+}
+
+#[test]
+fn test_shutdown_closes_clients() {
+    let server = GroundServer::bind().unwrap();
+    let mut client = SatClient::connect(server.local_addr(), 3).unwrap();
+    assert!(server.wait_for(3, true, TIMEOUT));
+
+    server.shutdown();
+    assert!(client.recv_timeout(TIMEOUT).unwrap().is_none());
+}
+
+#[test]
+fn test_closed_connections_are_forgotten() {
+    let server = GroundServer::bind().unwrap();
+    for sat_id in 1..=5 {
+        let client = SatClient::connect(server.local_addr(), sat_id).unwrap();
+        assert!(server.wait_for(sat_id, true, TIMEOUT));
+        client.disconnect();
+        assert!(server.wait_for(sat_id, false, TIMEOUT));
+    }
+
+    let deadline = Instant::now() + TIMEOUT;
+    while server.connections() > 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(2));
+    }
+    assert_eq!(server.connections(), 0);
+}
+
+#[test]
+fn test_silent_connection_is_closed_after_register_timeout() {
+    let server = GroundServer::bind().unwrap();
+    let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+    let start = Instant::now();
+    assert_eq!(stream.read(&mut [0u8; 1]).unwrap(), 0);
+    assert!(start.elapsed() >= REGISTER_TIMEOUT);
+}
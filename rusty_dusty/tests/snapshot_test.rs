@@ -0,0 +1,29 @@
+use std::env;
+
+use rusty_dusty::alloc_count::CountingAllocator;
+use rusty_dusty::demo;
+use rusty_dusty::snapshot::{self, Status};
+
+// Snapshots of the own demo record allocation counts
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_demo_output_matches_snapshots() {
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut failures = vec![];
+
+    for demo in demo::all() {
+        match snapshot::check(demo, update).expect("failed to run demo") {
+            Status::Matched | Status::Updated => {}
+            Status::Missing => failures.push(format!("{}: no snapshot recorded", demo.name())),
+            Status::Changed(diff) => failures.push(format!("{}: output changed\n{}", demo.name(), diff)),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\nRun with UPDATE_SNAPSHOTS=1 to re-bless the snapshots.",
+        failures.join("\n")
+    );
+}